@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS replays (
+	id VARCHAR PRIMARY KEY,
+	seed INT8 NOT NULL,
+	events TEXT NOT NULL
+)
\ No newline at end of file
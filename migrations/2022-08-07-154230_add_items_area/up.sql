@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE items ADD COLUMN IF NOT EXISTS area BOOLEAN NOT NULL DEFAULT FALSE
\ No newline at end of file
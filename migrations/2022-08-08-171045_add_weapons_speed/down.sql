@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE weapons DROP COLUMN IF EXISTS speed;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS speed INT DEFAULT 0
\ No newline at end of file
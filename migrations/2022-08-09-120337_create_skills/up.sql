@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS skills (
+	id SERIAL PRIMARY KEY,
+	name VARCHAR NOT NULL,
+	description TEXT NOT NULL,
+	icon CHAR NOT NULL,
+	lua TEXT NOT NULL,
+	cost INT NOT NULL DEFAULT 0,
+	cooldown INT NOT NULL DEFAULT 0,
+	area BOOLEAN NOT NULL DEFAULT FALSE
+)
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS battles (
+	id VARCHAR PRIMARY KEY,
+	guild_id INT8 NOT NULL,
+	channel_id INT8 NOT NULL,
+	message_id INT8 NOT NULL,
+	state TEXT NOT NULL
+)
\ No newline at end of file
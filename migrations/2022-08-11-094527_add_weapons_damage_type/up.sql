@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS damage_type VARCHAR DEFAULT 'physical'
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS accuracy FLOAT8
\ No newline at end of file
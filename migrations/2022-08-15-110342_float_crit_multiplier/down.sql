@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE weapons DROP CONSTRAINT IF EXISTS weapons_crit_ratio_check;
+ALTER TABLE weapons DROP COLUMN IF EXISTS crit_ignores_armor;
+ALTER TABLE weapons ALTER COLUMN crit_multiplier TYPE INT USING round(crit_multiplier)::INT;
+ALTER TABLE weapons ALTER COLUMN crit_multiplier SET DEFAULT 2;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE weapons ALTER COLUMN crit_multiplier TYPE FLOAT8 USING crit_multiplier::FLOAT8;
+ALTER TABLE weapons ALTER COLUMN crit_multiplier SET DEFAULT 2.0;
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS crit_ignores_armor BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE weapons ADD CONSTRAINT weapons_crit_ratio_check CHECK (crit_ratio BETWEEN 0 AND 1)
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE weapons DROP COLUMN IF EXISTS thorns;
+ALTER TABLE weapons DROP COLUMN IF EXISTS lifesteal;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS lifesteal INT DEFAULT 0;
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS thorns INT DEFAULT 0;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE weapons DROP COLUMN IF EXISTS rarity;
+ALTER TABLE items DROP COLUMN IF EXISTS rarity;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE items ADD COLUMN IF NOT EXISTS rarity VARCHAR NOT NULL DEFAULT 'common';
+ALTER TABLE weapons ADD COLUMN IF NOT EXISTS rarity VARCHAR NOT NULL DEFAULT 'common';
\ No newline at end of file
@@ -0,0 +1,3 @@
+local thorns = random(20, 30);
+add_thorns("user", thorns);
+add_log_entry(user_name .. " put on spiky cactus armor, returning " .. thorns .. "% of the damage they take to their attackers.");
@@ -1,5 +1,5 @@
-local heal = math.random() < 0.5;
-local health = math.random(20, 35);
+local heal = random() < 0.5;
+local health = random(20, 35);
 if heal then
 	local healing = heal_opponent(health);
 	add_log_entry(user_name .. " flipped " .. healing .. " healing against " .. opponent_name .. ".");
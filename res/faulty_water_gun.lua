@@ -1,10 +1,10 @@
-local opponent_damage = math.random(30, 40);
-local backfire = math.random() < 0.1;
-local self_damage = math.random(50, 60);
+local opponent_damage = random(30, 40);
+local backfire = random() < 0.1;
+local self_damage = random(50, 60);
 if backfire then
-	local damage = damage_user(self_damage, 0);
+	local damage = damage_user(self_damage, 0, "water");
 	add_log_entry(user_name .. "'s water gun backfired, dealing " .. damage .. " damage to themselves.");
 else
-	local damage = damage_opponent(opponent_damage, 0);
+	local damage = damage_opponent(opponent_damage, 0, "water");
 	add_log_entry(user_name .. " splashed " .. opponent_name .. " with a water gun, dealing " .. damage .. " damage.");
 end
\ No newline at end of file
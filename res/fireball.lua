@@ -0,0 +1,3 @@
+local damage = damage_opponent(random(15, 25), 5, "fire");
+apply_status("opponent", "burn", 2, 4);
+add_log_entry(user_name .. " hurled a fireball at " .. opponent_name .. ", dealing " .. damage .. " damage and setting them ablaze.");
\ No newline at end of file
@@ -0,0 +1,13 @@
+function on_attack(damage)
+	local hits = recall("hits") + 1;
+	remember("hits", hits);
+	if hits > 1 then
+		local bonus = damage_opponent(2 * (hits - 1), 0);
+		add_log_entry("The hungry blade grows stronger, dealing " .. bonus .. " more damage to " .. opponent_name .. ".");
+	end
+end
+
+function on_kill(damage)
+	local healing = heal_user(damage);
+	add_log_entry("The hungry blade feeds " .. user_name .. ", healing " .. healing .. " health.");
+end
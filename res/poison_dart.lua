@@ -0,0 +1,3 @@
+local turns = random(2, 4);
+apply_status("opponent", "poison", turns, 5);
+add_log_entry(user_name .. " threw a poisoned dart at " .. opponent_name .. ", poisoning them for " .. turns .. " turns.");
\ No newline at end of file
@@ -0,0 +1,4 @@
+local resistance = random(30, 50);
+add_resistance("user", "electric", resistance);
+add_resistance("user", "water", 20);
+add_log_entry(user_name .. " pulled on rubber boots, shrugging off " .. resistance .. "% of electric damage and 20% of water damage.");
\ No newline at end of file
@@ -0,0 +1,2 @@
+apply_status("user", "focus", 2);
+add_log_entry(user_name .. " sharpened their weapon and took aim. Their next attack can't miss.");
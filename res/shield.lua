@@ -1,3 +1,3 @@
-local armor = math.random(5, 10);
+local armor = random(5, 10);
 add_armor(armor);
 add_log_entry(user_name .. " equipped a shield, gaining " .. armor .. " armor.");
\ No newline at end of file
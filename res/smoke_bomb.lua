@@ -0,0 +1,2 @@
+apply_status("user", "evasion", 2, 40);
+add_log_entry(user_name .. " threw down a smoke bomb, making them much harder to hit for 2 turns.");
@@ -0,0 +1,2 @@
+add_lifesteal("user", 25);
+add_log_entry(user_name .. " bit into a vampire fang. From now on, their hits will heal them.");
@@ -0,0 +1,71 @@
+use super::{
+	Battler,
+	engine::Action,
+};
+
+use rand::prelude::*;
+
+/// Decides the actions of battlers without a user behind them.
+pub trait Strategy: Send + Sync {
+	/// Chooses an action for `user`, given its living opponents and their indices in the battle.
+	fn choose_action(&self, user: &dyn Battler, opponents: &[(usize, &dyn Battler)], rng: &mut dyn RngCore) -> Action;
+}
+
+/// Attacks most of the time and casts a random skill, uses a random item or defends otherwise,
+/// picking targets at random.
+pub struct Balanced {
+	pub skill_chance: f64,
+	pub item_chance: f64,
+	pub defend_chance: f64,
+}
+
+impl Default for Balanced {
+	fn default() -> Self {
+		Self {
+			skill_chance: 0.3,
+			item_chance: 0.3,
+			defend_chance: 0.1,
+		}
+	}
+}
+
+impl Strategy for Balanced {
+	fn choose_action(&self, user: &dyn Battler, opponents: &[(usize, &dyn Battler)], rng: &mut dyn RngCore) -> Action {
+		let targets: Vec<usize> = opponents.iter().map(|(i, _)| *i).collect();
+		let target = if let Some(target) = targets.choose(rng) {
+			*target
+		} else {
+			return Action::Timeout;
+		};
+
+		if rng.gen_bool(self.skill_chance) {
+			let skills: Vec<_> = user.skills().iter().filter(|skill| user.can_cast(skill)).collect();
+
+			if let Some(skill) = skills.choose(rng) {
+				return Action::Skill {
+					skill: skill.id,
+					targets: if skill.area { targets } else { vec![target] },
+				};
+			}
+		}
+
+		if rng.gen_bool(self.item_chance) {
+			// Sorted so that the choice only depends on the RNG.
+			let mut items: Vec<_> = user.items().values().collect();
+			items.sort_by(|a, b| a.name.cmp(&b.name));
+
+			if let Some(item) = items.choose(rng) {
+				return Action::Item {
+					item: item.id,
+					targets: if item.area { targets } else { vec![target] },
+				};
+			}
+		}
+
+		if rng.gen_bool(self.defend_chance) {
+			return Action::Defend;
+		}
+
+		Action::Attack { target }
+	}
+}
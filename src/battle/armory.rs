@@ -0,0 +1,119 @@
+use crate::{
+	prelude::*,
+	model::{QueryItem, QuerySkill, QueryWeapon},
+	util::establish_connection,
+};
+use super::{
+	Battler,
+	item::{self, Item},
+	rarity::RarityWeights,
+	skill::Skill,
+	weapon::Weapon,
+};
+
+use std::collections::HashMap;
+use diesel::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Every weapon, item and skill a loadout can be drawn from.
+#[derive(Clone)]
+pub struct Armory {
+	pub weapons: Vec<Weapon>,
+	pub items: Vec<Item>,
+	pub skills: Vec<Skill>,
+	/// How likely weapons and items of each rarity are to be drawn.
+	pub weights: RarityWeights,
+}
+
+/// The weapon, items and skills a battler starts with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Loadout {
+	pub weapon: Weapon,
+	#[serde(default)]
+	pub secondary: Option<Weapon>,
+	#[serde(deserialize_with = "item::deserialize_items")]
+	pub items: HashMap<i32, Item>,
+	pub skills: Vec<Skill>,
+}
+
+impl Loadout {
+	/// What a battler is currently carrying.
+	pub fn of(battler: &dyn Battler) -> Self {
+		Self {
+			weapon: battler.weapon().clone(),
+			secondary: battler.secondary_weapon().cloned(),
+			items: battler.items().clone(),
+			skills: battler.skills().to_vec(),
+		}
+	}
+}
+
+impl Armory {
+	/// Loads everything in order of ID, so the same seed always draws the same loadouts.
+	pub fn load() -> Result<Self, Error> {
+		let conn = establish_connection()?;
+
+		let items: Result<Vec<Item>, Error> = {
+			use crate::schema::items::dsl::*;
+			items.order(id).load::<QueryItem>(&conn)?.into_iter().map(Item::try_from).collect()
+		};
+
+		let weapons: Result<Vec<Weapon>, Error> = {
+			use crate::schema::weapons::dsl::*;
+			weapons.order(id).load::<QueryWeapon>(&conn)?.into_iter().map(Weapon::try_from).collect()
+		};
+
+		let skills: Result<Vec<Skill>, Error> = {
+			use crate::schema::skills::dsl::*;
+			skills.order(id).load::<QuerySkill>(&conn)?.into_iter().map(Skill::try_from).collect()
+		};
+
+		Ok(Self {
+			weapons: weapons?,
+			items: items?,
+			skills: skills?,
+			weights: RarityWeights::default(),
+		})
+	}
+
+	pub fn with_weights(mut self, weights: RarityWeights) -> Self {
+		self.weights = weights;
+		self
+	}
+
+	/// Draws a random weapon with a different one to switch to, up to three items in stacks of up to two, and up to two different skills.
+	///
+	/// Weapons and items are weighted by their rarity.
+	pub fn draw_loadout(&self, rng: &mut impl Rng) -> Result<Loadout, Error> {
+		let copies: Vec<&Item> = self.items.iter().flat_map(|item| [item, item]).collect();
+		let items = if copies.is_empty() {
+			HashMap::new()
+		} else {
+			copies
+				.choose_multiple_weighted(rng, 3, |item| self.weights.of(item.rarity))?
+				.fold(HashMap::new(), |mut acc, &item| {
+					acc.entry(item.id).or_insert(Item { quantity: 0, ..item.clone() }).quantity += 1;
+					acc
+				})
+		};
+
+		let weapon = self.weapons
+			.choose_weighted(rng, |weapon| self.weights.of(weapon.rarity))
+			.map_err(|e| format!("Could not draw a weapon: {e}."))?
+			.clone();
+
+		let others: Vec<&Weapon> = self.weapons.iter().filter(|secondary| secondary.name != weapon.name).collect();
+		let secondary = others
+			.choose_weighted(rng, |secondary| self.weights.of(secondary.rarity))
+			.ok()
+			.map(|&secondary| secondary.clone());
+
+		let skills = self.skills
+			.iter()
+			.cloned()
+			.choose_multiple(rng, 2);
+
+		Ok(Loadout { weapon, secondary, items, skills })
+	}
+}
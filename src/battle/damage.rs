@@ -0,0 +1,90 @@
+use std::{fmt::Display, str::FromStr};
+use serde::{Deserialize, Serialize};
+
+/// The strongest a resistance can be, in percent. Anything past it would heal instead.
+pub const MAX_RESISTANCE: isize = 100;
+/// The strongest a weakness can be, in percent, which doubles the damage taken.
+pub const MAX_WEAKNESS: isize = -100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+	#[default]
+	Physical,
+	Fire,
+	Water,
+	Electric,
+	Poison,
+	/// Ignores resistances and weaknesses, like sudden death.
+	True,
+}
+
+impl DamageType {
+	pub fn icon(&self) -> char {
+		match self {
+			DamageType::Physical => '🗡',
+			DamageType::Fire => '🔥',
+			DamageType::Water => '💧',
+			DamageType::Electric => '⚡',
+			DamageType::Poison => '🧪',
+			DamageType::True => '✨',
+		}
+	}
+}
+
+impl Display for DamageType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			DamageType::Physical => "physical",
+			DamageType::Fire => "fire",
+			DamageType::Water => "water",
+			DamageType::Electric => "electric",
+			DamageType::Poison => "poison",
+			DamageType::True => "true",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for DamageType {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"physical" => Ok(DamageType::Physical),
+			"fire" => Ok(DamageType::Fire),
+			"water" => Ok(DamageType::Water),
+			"electric" => Ok(DamageType::Electric),
+			"poison" => Ok(DamageType::Poison),
+			"true" => Ok(DamageType::True),
+			other => Err(format!("Unknown damage type {other}.")),
+		}
+	}
+}
+
+/// Scales damage by a resistance in percent, where negative resistances are weaknesses.
+pub fn apply_resistance(damage: usize, resistance: isize) -> usize {
+	let resistance = resistance.clamp(MAX_WEAKNESS, MAX_RESISTANCE);
+	if resistance >= 0 {
+		damage.saturating_sub(damage.saturating_mul(resistance as usize) / 100)
+	} else {
+		damage.saturating_add(damage.saturating_mul(resistance.unsigned_abs()) / 100)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn resistance_scales_damage() {
+		assert_eq!(apply_resistance(100, 25), 75);
+		assert_eq!(apply_resistance(100, -25), 125);
+	}
+
+	#[test]
+	fn resistance_is_clamped() {
+		assert_eq!(apply_resistance(100, MAX_RESISTANCE * 2), 0);
+		assert_eq!(apply_resistance(100, MAX_WEAKNESS * 2), 200);
+	}
+}
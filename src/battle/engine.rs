@@ -0,0 +1,857 @@
+use crate::prelude::*;
+use super::{
+	Battler,
+	Weapon,
+	armory::Loadout,
+	damage::DamageType,
+	log::{Entry, Log},
+	replay::{BattlerSnapshot, Event, Replay},
+	rng::BattleRng,
+	snapshot::{BattlerRef, StoredBattler},
+	status::{PERMANENT, Status, StatusKind, TickPhase, tick_statuses},
+};
+
+use std::cmp::Reverse;
+use async_trait::async_trait;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How much initiative a battler needs to take a turn.
+const INITIATIVE_THRESHOLD: usize = 1000;
+/// How much incoming damage defending blocks, in percent.
+const GUARD_REDUCTION: usize = 50;
+/// How much armor defending grants until the battler's next turn.
+const GUARD_ARMOR: usize = 2;
+/// How much energy a battler recovers at the start of its turn.
+const ENERGY_REGEN: usize = 10;
+/// How many turns in a row a battler can time out before forfeiting, unless set otherwise.
+pub const DEFAULT_MAX_TIMEOUTS: usize = 3;
+/// The round in which sudden death starts.
+const SUDDEN_DEATH_ROUND: usize = 20;
+/// How much damage sudden death deals in its first round, growing by as much every round after.
+const SUDDEN_DEATH_DAMAGE: usize = 5;
+/// How much armor battlers lose every turn during sudden death.
+const ARMOR_DECAY: usize = 3;
+/// The round after which the battle is decided by remaining health.
+const MAX_ROUNDS: usize = 30;
+
+/// An action a battler can take on their turn.
+///
+/// Targets are indices into the battle's battlers.
+#[derive(Clone, Debug)]
+pub enum Action {
+	Attack { target: usize },
+	/// Uses one of the items with this database ID.
+	Item { item: i32, targets: Vec<usize> },
+	Skill { skill: Uuid, targets: Vec<usize> },
+	/// Cuts incoming damage until the battler's next turn.
+	Defend,
+	/// Swaps to the secondary weapon, which only takes up the turn if switching isn't free.
+	Switch,
+	Surrender,
+	Timeout,
+}
+
+/// Presents a battle and collects the actions of its battlers.
+#[async_trait]
+pub trait Frontend: Send {
+	async fn choose_action(&mut self, engine: &BattleEngine) -> Result<Action, Error>;
+	/// Called after every turn, so the frontend can save the battle.
+	async fn checkpoint(&mut self, _engine: &BattleEngine) -> Result<(), Error> {
+		Ok(())
+	}
+	async fn finish(&mut self, engine: &BattleEngine) -> Result<(), Error>;
+}
+
+/// How a finished battle was decided.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+	/// Every other team was knocked out.
+	Knockout(usize),
+	/// The turn limit was reached, and this team had the largest share of its health left.
+	Health(usize),
+	/// The last teams standing were knocked out at the same time.
+	DoubleKnockout,
+	/// The turn limit was reached with the leading teams on the same share of their health.
+	Draw,
+}
+
+impl Outcome {
+	pub fn winning_team(&self) -> Option<usize> {
+		match self {
+			Outcome::Knockout(team) | Outcome::Health(team) => Some(*team),
+			Outcome::DoubleKnockout | Outcome::Draw => None,
+		}
+	}
+}
+
+/// How ties are broken when battlers are ready to act at the same time.
+///
+/// Battlers with equal speed take turns in this order, while faster battlers go first and act more often.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum TurnOrder {
+	/// Teams take turns, starting with a random team.
+	#[default]
+	Alternating,
+	/// Battlers act in a random order.
+	Random,
+	/// Battlers act in the order they were given.
+	Fixed,
+}
+
+/// Everything needed to pick a battle back up, generic over how battlers are stored.
+#[derive(Serialize, Deserialize)]
+pub struct EngineSnapshot<B> {
+	id: Uuid,
+	battlers: Vec<B>,
+	teams: Vec<usize>,
+	order: Vec<usize>,
+	initiative: Vec<usize>,
+	current: usize,
+	timeouts: Vec<usize>,
+	max_timeouts: usize,
+	turns: Vec<usize>,
+	sudden_death_started: bool,
+	log: Log,
+	rng: BattleRng,
+	#[serde(default)]
+	turn_order: TurnOrder,
+	#[serde(default)]
+	loadouts: Vec<Loadout>,
+}
+
+impl<B> EngineSnapshot<B> {
+	pub fn id(&self) -> &Uuid {
+		&self.id
+	}
+}
+
+pub struct BattleEngine {
+	id: Uuid,
+	battlers: Vec<Box<dyn Battler>>,
+	teams: Vec<usize>,
+	order: Vec<usize>,
+	initiative: Vec<usize>,
+	current: usize,
+	timeouts: Vec<usize>,
+	max_timeouts: usize,
+	/// How many turns each battler has had.
+	turns: Vec<usize>,
+	sudden_death_started: bool,
+	log: Log,
+	rng: BattleRng,
+	turn_order: TurnOrder,
+	/// What each battler started with, so the battle can be rematched with the same loadouts.
+	loadouts: Vec<Loadout>,
+	/// Whether switching weapons leaves the turn to do something else.
+	free_switch: bool,
+	/// Whether the current battler has already switched weapons for free this turn.
+	switched: bool,
+}
+
+impl BattleEngine {
+	/// Creates a battle between teams of battlers.
+	///
+	/// The battle's randomness continues from `rng`, which should also have drawn the battlers' loadouts.
+	pub fn new(teams: Vec<Vec<Box<dyn Battler>>>, turn_order: TurnOrder, mut rng: BattleRng) -> Self {
+		let mut battlers = vec![];
+		let mut battler_teams = vec![];
+		let mut members: Vec<Vec<usize>> = vec![];
+		for (team, team_battlers) in teams.into_iter().enumerate() {
+			let mut team_members = vec![];
+			for battler in team_battlers {
+				team_members.push(battlers.len());
+				battlers.push(battler);
+				battler_teams.push(team);
+			}
+			members.push(team_members);
+		}
+
+		let order = match turn_order {
+			TurnOrder::Alternating => {
+				let start = rng.gen_range(0..members.len());
+				let rounds = members.iter().map(Vec::len).max().unwrap_or(0);
+
+				let mut order = vec![];
+				for round in 0..rounds {
+					for offset in 0..members.len() {
+						if let Some(&battler) = members[(start + offset) % members.len()].get(round) {
+							order.push(battler);
+						}
+					}
+				}
+				order
+			},
+			TurnOrder::Random => {
+				let mut order: Vec<usize> = (0..battlers.len()).collect();
+				order.shuffle(&mut rng);
+				order
+			},
+			TurnOrder::Fixed => (0..battlers.len()).collect(),
+		};
+
+		let mut log = Log::new();
+		log.record(Event::Start {
+			seed: rng.seed(),
+			battlers: BattlerSnapshot::of(&battlers),
+		});
+
+		let loadouts = battlers.iter().map(|battler| Loadout::of(battler.as_ref())).collect();
+
+		let mut engine = Self {
+			id: Uuid::new_v4(),
+			initiative: vec![0; battlers.len()],
+			timeouts: vec![0; battlers.len()],
+			max_timeouts: DEFAULT_MAX_TIMEOUTS,
+			turns: vec![0; battlers.len()],
+			sudden_death_started: false,
+			battlers,
+			teams: battler_teams,
+			order,
+			current: 0,
+			log,
+			rng,
+			turn_order,
+			loadouts,
+			free_switch: false,
+			switched: false,
+		};
+		engine.advance();
+		engine
+	}
+
+	/// Continues a battle from a snapshot taken with [`BattleEngine::snapshot`].
+	pub fn restore(snapshot: EngineSnapshot<StoredBattler>) -> Self {
+		Self {
+			id: snapshot.id,
+			battlers: snapshot.battlers.into_iter().map(StoredBattler::into_battler).collect(),
+			teams: snapshot.teams,
+			order: snapshot.order,
+			initiative: snapshot.initiative,
+			current: snapshot.current,
+			timeouts: snapshot.timeouts,
+			max_timeouts: snapshot.max_timeouts,
+			turns: snapshot.turns,
+			sudden_death_started: snapshot.sudden_death_started,
+			log: snapshot.log,
+			rng: snapshot.rng,
+			turn_order: snapshot.turn_order,
+			loadouts: snapshot.loadouts,
+			free_switch: false,
+			switched: false,
+		}
+	}
+
+	pub fn snapshot(&self) -> EngineSnapshot<BattlerRef<'_>> {
+		EngineSnapshot {
+			id: self.id,
+			battlers: self.battlers.iter().map(|battler| battler.snapshot()).collect(),
+			teams: self.teams.clone(),
+			order: self.order.clone(),
+			initiative: self.initiative.clone(),
+			current: self.current,
+			timeouts: self.timeouts.clone(),
+			max_timeouts: self.max_timeouts,
+			turns: self.turns.clone(),
+			sudden_death_started: self.sudden_death_started,
+			log: self.log.clone(),
+			rng: self.rng.clone(),
+			turn_order: self.turn_order,
+			loadouts: self.loadouts.clone(),
+		}
+	}
+
+	/// Sets how many turns in a row a battler can time out before it forfeits.
+	pub fn set_max_timeouts(&mut self, max_timeouts: usize) {
+		self.max_timeouts = max_timeouts;
+	}
+
+	/// Sets whether switching weapons leaves the turn to do something else, once per turn.
+	pub fn set_free_switch(&mut self, free_switch: bool) {
+		self.free_switch = free_switch;
+	}
+
+	/// Whether the current battler can switch weapons right now.
+	pub fn can_switch(&self) -> bool {
+		self.current().secondary_weapon().is_some() && !self.switched
+	}
+
+	pub fn id(&self) -> &Uuid {
+		&self.id
+	}
+
+	pub fn battlers(&self) -> &[Box<dyn Battler>] {
+		&self.battlers
+	}
+
+	pub fn log(&self) -> &Log {
+		&self.log
+	}
+
+	pub fn turn_order(&self) -> TurnOrder {
+		self.turn_order
+	}
+
+	/// What each battler started the battle with, which is empty for battles saved before loadouts were kept.
+	pub fn loadouts(&self) -> &[Loadout] {
+		&self.loadouts
+	}
+
+	/// New battlers for a rematch against the same opponents on the same teams, with a loadout for each battler.
+	pub fn rematch_teams(&self, loadouts: Vec<Loadout>) -> Vec<Vec<Box<dyn Battler>>> {
+		let mut teams: Vec<Vec<Box<dyn Battler>>> = vec![];
+		for ((battler, &team), loadout) in self.battlers.iter().zip(&self.teams).zip(loadouts) {
+			if teams.len() <= team {
+				teams.resize_with(team + 1, Vec::new);
+			}
+			teams[team].push(battler.rematch(loadout));
+		}
+		teams
+	}
+
+	pub fn seed(&self) -> u64 {
+		self.rng.seed()
+	}
+
+	pub fn replay(&self) -> Replay {
+		Replay {
+			id: self.id,
+			seed: self.seed(),
+			events: self.log.events().to_vec(),
+		}
+	}
+
+	/// The index of the battler whose turn it is.
+	pub fn turn(&self) -> usize {
+		self.current
+	}
+
+	/// How many turns every battler still standing has had.
+	pub fn round(&self) -> usize {
+		self.battlers
+			.iter()
+			.zip(&self.turns)
+			.filter(|(battler, _)| battler.health() > 0)
+			.map(|(_, &turns)| turns)
+			.min()
+			.unwrap_or(0)
+	}
+
+	pub fn current(&self) -> &dyn Battler {
+		self.battlers[self.turn()].as_ref()
+	}
+
+	pub fn team(&self, battler: usize) -> usize {
+		self.teams[battler]
+	}
+
+	/// Whether any team has more than one member.
+	pub fn has_teams(&self) -> bool {
+		self.teams.iter().any(|team| self.teams.iter().filter(|other| *other == team).count() > 1)
+	}
+
+	fn enemies_of(&self, battler: usize) -> Vec<usize> {
+		(0..self.battlers.len())
+			.filter(|&other| self.teams[other] != self.teams[battler] && self.battlers[other].health() > 0)
+			.collect()
+	}
+
+	/// The battlers the current battler can target.
+	pub fn targets(&self) -> Vec<usize> {
+		self.enemies_of(self.turn())
+	}
+
+	/// Lets the current battler's strategy choose its action using the battle's RNG.
+	///
+	/// Returns `None` if the battler has no strategy and must be asked through the frontend.
+	pub fn decide(&mut self) -> Option<Action> {
+		let turn = self.turn();
+		let enemies = self.enemies_of(turn);
+		let user = self.battlers[turn].as_ref();
+		let strategy = user.strategy()?;
+		let opponents: Vec<(usize, &dyn Battler)> = enemies
+			.into_iter()
+			.map(|enemy| (enemy, self.battlers[enemy].as_ref()))
+			.collect();
+
+		Some(strategy.choose_action(user, &opponents, &mut self.rng))
+	}
+
+	fn living_teams(&self) -> Vec<usize> {
+		let mut teams: Vec<usize> = self.battlers
+			.iter()
+			.zip(&self.teams)
+			.filter(|(battler, _)| battler.health() > 0)
+			.map(|(_, &team)| team)
+			.collect();
+		teams.sort_unstable();
+		teams.dedup();
+		teams
+	}
+
+	/// The remaining and maximum health of a team's members, added up.
+	fn team_health(&self, team: usize) -> (usize, usize) {
+		self.battlers
+			.iter()
+			.zip(&self.teams)
+			.filter(|&(_, other)| *other == team)
+			.fold((0, 0), |(health, max_health), (battler, _)| (health + battler.health(), max_health + battler.max_health()))
+	}
+
+	pub fn health_percentage(&self, team: usize) -> f64 {
+		let (health, max_health) = self.team_health(team);
+		health as f64 / max_health.max(1) as f64 * 100.0
+	}
+
+	/// How the battle was decided, or `None` while it is still going.
+	pub fn outcome(&self) -> Option<Outcome> {
+		let living = self.living_teams();
+		match living[..] {
+			[] => Some(Outcome::DoubleKnockout),
+			[team] => Some(Outcome::Knockout(team)),
+			_ if self.round() >= MAX_ROUNDS => {
+				// Fractions are compared by cross-multiplying, so equal shares are never split by rounding.
+				let mut shares: Vec<(usize, u128, u128)> = living
+					.into_iter()
+					.map(|team| {
+						let (health, max_health) = self.team_health(team);
+						(team, health as u128, max_health.max(1) as u128)
+					})
+					.collect();
+				shares.sort_by(|a, b| (b.1 * a.2).cmp(&(a.1 * b.2)));
+
+				let (leader, health, max_health) = shares[0];
+				let (_, runner_up_health, runner_up_max_health) = shares[1];
+				if health * runner_up_max_health == runner_up_health * max_health {
+					Some(Outcome::Draw)
+				} else {
+					Some(Outcome::Health(leader))
+				}
+			},
+			_ => None,
+		}
+	}
+
+	/// The battle is over once at most one team is left standing or the turn limit is reached.
+	pub fn is_over(&self) -> bool {
+		self.outcome().is_some()
+	}
+
+	pub fn winning_team(&self) -> Option<usize> {
+		self.outcome().and_then(|outcome| outcome.winning_team())
+	}
+
+	/// Every member of the winning team, including those who were knocked out.
+	pub fn winners(&self) -> Vec<&dyn Battler> {
+		if let Some(winning_team) = self.winning_team() {
+			self.battlers
+				.iter()
+				.zip(&self.teams)
+				.filter(|&(_, team)| *team == winning_team)
+				.map(|(battler, _)| battler.as_ref())
+				.collect()
+		} else {
+			vec![]
+		}
+	}
+
+	/// Fills every living battler's initiative by its speed until someone can act.
+	///
+	/// A battler much faster than the others can get several turns in a row.
+	fn advance(&mut self) {
+		let living: Vec<usize> = self.order
+			.iter()
+			.copied()
+			.filter(|&battler| self.battlers[battler].health() > 0)
+			.collect();
+		if living.is_empty() {
+			return;
+		}
+
+		loop {
+			let ready = living
+				.iter()
+				.copied()
+				.filter(|&battler| self.initiative[battler] >= INITIATIVE_THRESHOLD)
+				.min_by_key(|&battler| Reverse(self.initiative[battler]));
+
+			if let Some(battler) = ready {
+				self.initiative[battler] -= INITIATIVE_THRESHOLD;
+				self.current = battler;
+				return;
+			}
+
+			let ticks = living
+				.iter()
+				.map(|&battler| {
+					let speed = self.battlers[battler].initiative();
+					(INITIATIVE_THRESHOLD - self.initiative[battler]).div_ceil(speed)
+				})
+				.min()
+				.unwrap_or(1);
+
+			for &battler in &living {
+				let speed = self.battlers[battler].initiative();
+				self.initiative[battler] = self.initiative[battler].saturating_add(ticks.saturating_mul(speed));
+			}
+		}
+	}
+
+	/// Deals escalating damage and wears away armor once the battle has gone on too long.
+	/// Healing is blocked for the rest of the battle.
+	fn sudden_death(&mut self) {
+		let round = self.round();
+		if round < SUDDEN_DEATH_ROUND {
+			return;
+		}
+
+		if !self.sudden_death_started {
+			self.sudden_death_started = true;
+			self.log.add(Entry::SuddenDeath);
+			for battler in &mut self.battlers {
+				battler.apply_status(Status::new(StatusKind::Wounded, PERMANENT, 0));
+			}
+		}
+
+		let battler = &mut self.battlers[self.current];
+		battler.set_armor(battler.armor().saturating_sub(ARMOR_DECAY));
+		let damage = battler.damage(SUDDEN_DEATH_DAMAGE * (round - SUDDEN_DEATH_ROUND + 1), usize::MAX, DamageType::True);
+		self.log.add(Entry::SuddenDeathDamage(battler.name().clone(), damage));
+	}
+
+	/// Ticks the current battler's start-of-turn statuses, along with sudden death.
+	///
+	/// Returns whether the battler can act. If it is stunned or knocked out, its turn has already been passed.
+	pub fn begin_turn(&mut self) -> bool {
+		let turn = self.turn();
+		self.battlers[turn].add_energy(ENERGY_REGEN as isize);
+		self.sudden_death();
+		let stunned = tick_statuses(&mut *self.battlers[turn], TickPhase::Start, &mut self.log);
+
+		if stunned || self.battlers[turn].health() == 0 {
+			self.log.record(Event::Turn {
+				battler: self.battlers[turn].name().clone(),
+				action: if stunned { "Stunned".into() } else { "Knocked out".into() },
+			});
+			self.end_turn();
+			false
+		} else {
+			true
+		}
+	}
+
+	fn end_turn(&mut self) {
+		let turn = self.turn();
+		tick_statuses(&mut *self.battlers[turn], TickPhase::End, &mut self.log);
+		for cooldown in self.battlers[turn].cooldowns_mut().values_mut() {
+			*cooldown = cooldown.saturating_sub(1);
+		}
+
+		self.log.record(Event::State(BattlerSnapshot::of(&self.battlers)));
+		self.turns[turn] += 1;
+		self.switched = false;
+		self.advance();
+	}
+
+	/// Resolves an action for the current battler and passes the turn, unless it was a free weapon switch.
+	///
+	/// [`BattleEngine::begin_turn`] must be called first.
+	pub fn act(&mut self, action: Action) -> Result<(), Error> {
+		let turn = self.turn();
+		let valid_targets = self.targets();
+
+		let user = &self.battlers[turn];
+		let target_names = |targets: &[usize]| targets
+			.iter()
+			.map(|&target| self.battlers[target].name().as_str())
+			.collect::<Vec<_>>()
+			.join(" and ");
+		let description = match &action {
+			Action::Attack { target } => {
+				if !valid_targets.contains(target) {
+					return Err(format!("Battler {target} cannot be attacked.").into());
+				}
+
+				format!("Attack {} with {}", target_names(&[*target]), user.weapon().name)
+			},
+			Action::Item { item, targets } => {
+				let item = user.items().get(item).ok_or(format!("Item ID {} not found.", item))?;
+				if targets.is_empty() || !targets.iter().all(|target| valid_targets.contains(target)) {
+					return Err(format!("{} cannot be used on those targets.", item.name).into());
+				}
+				if !item.area && targets.len() > 1 {
+					return Err(format!("{} can only be used on one target.", item.name).into());
+				}
+
+				format!("Use {} on {}", item.name, target_names(targets))
+			},
+			Action::Skill { skill, targets } => {
+				let skill = user.skills().iter().find(|s| s.id == *skill).ok_or(format!("Skill ID {} not found.", skill))?;
+				if !user.can_cast(skill) {
+					return Err(format!("{} cannot be cast right now.", skill.name).into());
+				}
+				if targets.is_empty() || !targets.iter().all(|target| valid_targets.contains(target)) {
+					return Err(format!("{} cannot be used on those targets.", skill.name).into());
+				}
+				if !skill.area && targets.len() > 1 {
+					return Err(format!("{} can only be used on one target.", skill.name).into());
+				}
+
+				format!("Cast {} on {}", skill.name, target_names(targets))
+			},
+			Action::Switch => {
+				if !self.can_switch() {
+					return Err(format!("{} cannot switch weapons right now.", user.name()).into());
+				}
+
+				format!("Switch to {}", user.secondary_weapon().map_or("", |weapon| weapon.name.as_str()))
+			},
+			Action::Defend => "Defend".into(),
+			Action::Surrender => "Surrender".into(),
+			Action::Timeout => "Timeout".into(),
+		};
+		self.log.record(Event::Turn {
+			battler: user.name().clone(),
+			action: description,
+		});
+
+		if let Action::Timeout = action {
+			self.timeouts[turn] += 1;
+		} else {
+			self.timeouts[turn] = 0;
+		}
+
+		match action {
+			Action::Attack { target } => {
+				let (user, mut targets) = split_mut(&mut self.battlers, turn, &[target]);
+				// Taken out of the user while attacking, since its script can affect the user.
+				let mut weapon = user.weapon().clone();
+				let result = weapon.attack(&mut **user, &mut **targets.remove(0), &mut self.log, &mut self.rng);
+				if weapon.is_broken() {
+					self.log.add(Entry::WeaponBroke(weapon.icon.clone(), user.name().clone(), weapon.name.clone()));
+					weapon = Weapon::default();
+				}
+				*user.weapon_mut() = weapon;
+				result?;
+			},
+			Action::Item { item, targets } => {
+				let item = self.battlers[turn]
+					.take_item(item)
+					.ok_or(format!("Item ID {} not found.", item))?;
+
+				let (user, targets) = split_mut(&mut self.battlers, turn, &targets);
+				let targets = targets.into_iter().map(|target| -> &mut dyn Battler { &mut **target }).collect();
+				item.use_item(&mut **user, targets, &mut self.log, &mut self.rng)?;
+			},
+			Action::Skill { skill, targets } => {
+				let skill = self.battlers[turn]
+					.skills()
+					.iter()
+					.find(|s| s.id == skill)
+					.ok_or(format!("Skill ID {} not found.", skill))?
+					.clone();
+
+				let (user, targets) = split_mut(&mut self.battlers, turn, &targets);
+				user.add_energy(-(skill.cost as isize));
+				// One more than the cooldown, since it ticks down at the end of this turn.
+				user.cooldowns_mut().insert(skill.id, skill.cooldown + 1);
+				let targets = targets.into_iter().map(|target| -> &mut dyn Battler { &mut **target }).collect();
+				skill.cast(&mut **user, targets, &mut self.log, &mut self.rng)?;
+			},
+			Action::Switch => {
+				let user = &mut self.battlers[turn];
+				user.switch_weapons();
+				self.log.add(Entry::Switch(user.weapon().icon.clone(), user.name().clone(), user.weapon().name.clone()));
+
+				if self.free_switch {
+					self.switched = true;
+					return Ok(());
+				}
+			},
+			Action::Defend => {
+				let user = &mut self.battlers[turn];
+				user.apply_status(Status::new(StatusKind::Guard, 1, GUARD_REDUCTION).with_armor(GUARD_ARMOR));
+				self.log.add(Entry::Defend(user.name().clone(), GUARD_ARMOR));
+			},
+			Action::Surrender => {
+				let user = &mut self.battlers[turn];
+				self.log.add(Entry::Surrender(user.name().clone()));
+				user.set_health(0);
+			},
+			Action::Timeout => {
+				let user = &mut self.battlers[turn];
+				if self.timeouts[turn] >= self.max_timeouts {
+					self.log.add(Entry::Forfeit(user.name().clone(), self.timeouts[turn]));
+					user.set_health(0);
+				} else {
+					self.log.add(Entry::Timeout(user.name().clone()));
+				}
+			},
+		}
+
+		self.end_turn();
+		Ok(())
+	}
+
+	pub async fn run(&mut self, frontend: &mut dyn Frontend) -> Result<(), Error> {
+		while !self.is_over() {
+			if self.begin_turn() {
+				// A free weapon switch leaves the battler to choose again.
+				loop {
+					let action = if let Some(action) = self.decide() {
+						action
+					} else {
+						frontend.choose_action(self).await?
+					};
+					let free_switch = matches!(action, Action::Switch) && self.free_switch;
+					self.act(action)?;
+					if !free_switch {
+						break;
+					}
+				}
+			}
+
+			frontend.checkpoint(self).await?;
+		}
+
+		let winners: Vec<String> = self.winners().iter().map(|winner| winner.name().clone()).collect();
+		let winner = if winners.is_empty() { None } else { Some(winners.join(" & ")) };
+		self.log.record(Event::End { winner });
+		frontend.finish(self).await
+	}
+}
+
+/// Borrows a user and its targets mutably at the same time.
+fn split_mut<'a, T>(slice: &'a mut [T], user: usize, targets: &[usize]) -> (&'a mut T, Vec<&'a mut T>) {
+	let mut user_ref = None;
+	let mut target_refs = vec![];
+	for (i, item) in slice.iter_mut().enumerate() {
+		if i == user {
+			user_ref = Some(item);
+		} else if targets.contains(&i) {
+			target_refs.push(item);
+		}
+	}
+
+	(user_ref.expect("User index out of bounds."), target_refs)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::battle::{Balanced, Loadout, NpcBattler, Weapon};
+
+	use std::collections::HashMap;
+
+	fn npc(name: &str, speed: usize) -> Box<dyn Battler> {
+		let loadout = Loadout {
+			weapon: Weapon::default(),
+			secondary: None,
+			items: HashMap::new(),
+			skills: vec![],
+		};
+		let mut npc = NpcBattler::with_loadout(name, None, Box::new(Balanced::default()), loadout);
+		npc.set_speed(speed);
+		Box::new(npc)
+	}
+
+	/// The next `count` turns, starting with the current one.
+	fn turns(engine: &mut BattleEngine, count: usize) -> Vec<usize> {
+		let mut turns = vec![engine.turn()];
+		while turns.len() < count {
+			engine.advance();
+			turns.push(engine.turn());
+		}
+		turns
+	}
+
+	#[test]
+	fn fast_battlers_get_extra_turns() {
+		let mut engine = BattleEngine::new(vec![vec![npc("Fast", 200)], vec![npc("Slow", 100)]], TurnOrder::Fixed, BattleRng::with_seed(1));
+		assert_eq!(turns(&mut engine, 6), vec![0, 0, 1, 0, 0, 1]);
+	}
+
+	#[test]
+	fn fixed_order_breaks_ties_in_the_given_order() {
+		let teams = vec![vec![npc("A", 100), npc("B", 100)], vec![npc("C", 100)]];
+		let mut engine = BattleEngine::new(teams, TurnOrder::Fixed, BattleRng::with_seed(1));
+		assert_eq!(turns(&mut engine, 6), vec![0, 1, 2, 0, 1, 2]);
+	}
+
+	#[test]
+	fn alternating_order_breaks_ties_between_teams() {
+		for seed in 0..16 {
+			let teams = vec![vec![npc("A", 100), npc("B", 100)], vec![npc("C", 100)]];
+			let mut engine = BattleEngine::new(teams, TurnOrder::Alternating, BattleRng::with_seed(seed));
+			let turns = turns(&mut engine, 3);
+
+			assert_ne!(engine.team(turns[0]), engine.team(turns[1]));
+			let mut battlers = turns.clone();
+			battlers.sort_unstable();
+			assert_eq!(battlers, vec![0, 1, 2]);
+		}
+	}
+
+	#[test]
+	fn random_order_is_the_same_for_the_same_seed() {
+		let order = |seed| {
+			let teams = vec![vec![npc("A", 100), npc("B", 100)], vec![npc("C", 100), npc("D", 100)]];
+			let mut engine = BattleEngine::new(teams, TurnOrder::Random, BattleRng::with_seed(seed));
+			turns(&mut engine, 8)
+		};
+		assert_eq!(order(7), order(7));
+	}
+
+	#[test]
+	fn battle_is_undecided_while_both_teams_stand() {
+		let engine = BattleEngine::new(vec![vec![npc("A", 100)], vec![npc("B", 100)]], TurnOrder::Fixed, BattleRng::with_seed(1));
+		assert_eq!(engine.outcome(), None);
+	}
+
+	#[test]
+	fn last_team_standing_wins_by_knockout() {
+		let mut engine = BattleEngine::new(vec![vec![npc("A", 100)], vec![npc("B", 100)]], TurnOrder::Fixed, BattleRng::with_seed(1));
+		engine.battlers[1].set_health(0);
+		assert_eq!(engine.outcome(), Some(Outcome::Knockout(0)));
+	}
+
+	#[test]
+	fn knocking_out_everyone_is_a_double_knockout() {
+		let mut engine = BattleEngine::new(vec![vec![npc("A", 100)], vec![npc("B", 100)]], TurnOrder::Fixed, BattleRng::with_seed(1));
+		engine.battlers[0].set_health(0);
+		engine.battlers[1].set_health(0);
+		assert_eq!(engine.outcome(), Some(Outcome::DoubleKnockout));
+		assert_eq!(engine.winning_team(), None);
+	}
+
+	#[test]
+	fn knocked_out_battlers_do_not_hold_back_the_round() {
+		let teams = vec![vec![npc("A", 100), npc("B", 100)], vec![npc("C", 100)]];
+		let mut engine = BattleEngine::new(teams, TurnOrder::Fixed, BattleRng::with_seed(1));
+		engine.battlers[1].set_health(0);
+		engine.turns = vec![4, 1, 4];
+		assert_eq!(engine.round(), 4);
+	}
+
+	#[test]
+	fn turn_limit_goes_to_the_largest_share_of_health() {
+		// The first team has more health left in total, but a smaller share of it.
+		let teams = vec![vec![npc("A", 100), npc("B", 100)], vec![npc("C", 100)]];
+		let mut engine = BattleEngine::new(teams, TurnOrder::Fixed, BattleRng::with_seed(1));
+		engine.battlers[0].set_health(70);
+		engine.battlers[1].set_health(20);
+		engine.battlers[2].set_health(50);
+		engine.turns = vec![MAX_ROUNDS; 3];
+		assert_eq!(engine.outcome(), Some(Outcome::Health(1)));
+	}
+
+	#[test]
+	fn turn_limit_with_equal_shares_is_a_draw() {
+		let mut engine = BattleEngine::new(vec![vec![npc("A", 100)], vec![npc("B", 100)]], TurnOrder::Fixed, BattleRng::with_seed(1));
+		engine.battlers[0].set_health(40);
+		engine.battlers[1].set_health(40);
+		engine.turns = vec![MAX_ROUNDS; 2];
+		assert_eq!(engine.outcome(), Some(Outcome::Draw));
+	}
+}
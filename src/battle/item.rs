@@ -0,0 +1,99 @@
+use crate::{
+	prelude::*,
+	battle::{
+		Battler,
+		log::Log,
+		rarity::Rarity,
+		script::Script,
+		util::option_description,
+	},
+	model::QueryItem,
+};
+
+use std::collections::HashMap;
+use poise::serenity_prelude::{CreateSelectMenuOption, ReactionType};
+use rand::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Item {
+	pub name: String,
+	/// The item's ID in the database, which is shared by every copy of it.
+	#[serde(deserialize_with = "deserialize_id")]
+	pub id: i32,
+	pub description: String,
+	pub icon: ReactionType,
+	pub lua: String,
+	pub area: bool,
+	/// How many of the item are in the stack.
+	#[serde(default = "default_quantity")]
+	pub quantity: usize,
+	/// How likely the item is to be drawn for a loadout.
+	#[serde(default)]
+	pub rarity: Rarity,
+}
+
+fn default_quantity() -> usize {
+	1
+}
+
+/// An item ID as saved, from before or after items were stacked.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedId {
+	Database(i32),
+	Legacy(Uuid),
+}
+
+/// Reads an item's ID, turning the random UUIDs that items had before they were stacked into negative IDs,
+/// which can't clash with the database's.
+fn deserialize_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+	Ok(match SavedId::deserialize(deserializer)? {
+		SavedId::Database(id) => id,
+		SavedId::Legacy(uuid) => -((uuid.as_u128() % i32::MAX as u128) as i32) - 1,
+	})
+}
+
+/// Reads a battler's items, keying them by their IDs rather than by the saved keys, which were UUIDs before items were stacked.
+pub fn deserialize_items<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<i32, Item>, D::Error> {
+	let items = HashMap::<String, Item>::deserialize(deserializer)?;
+	Ok(items.into_values().map(|item| (item.id, item)).collect())
+}
+
+impl Item {
+	/// Runs the item's script.
+	pub fn use_item(&self, user: &mut dyn Battler, targets: Vec<&mut dyn Battler>, log: &mut Log, rng: &mut impl Rng) -> Result<(), Error> {
+		Script::new(&self.name, &self.icon, &self.lua).run(user, targets, log, rng)
+	}
+
+	pub fn as_option<'a>(&self, o: &'a mut CreateSelectMenuOption) -> &'a mut CreateSelectMenuOption {
+		let label = if self.quantity > 1 {
+			format!("{} ×{}", self.name, self.quantity)
+		} else {
+			self.name.clone()
+		};
+
+		o.label(label)
+			.value(self.id)
+			.description(option_description(format!("{} • {}", self.rarity.label(), self.description)))
+			.emoji(self.icon.clone())
+	}
+}
+
+impl TryFrom<QueryItem> for Item {
+	type Error = Error;
+
+	fn try_from(item: QueryItem) -> Result<Self, Self::Error> {
+		Ok(Self {
+			name: item.name,
+			id: item.id,
+			description: item.description,
+			icon: item.icon.try_into()?,
+			lua: item.lua,
+			area: item.area,
+			quantity: 1,
+			rarity: item.rarity.parse()?,
+		})
+	}
+}
@@ -1,47 +1,93 @@
+use super::{replay::Event, status::StatusKind};
+
 use std::fmt::Display;
 use poise::serenity_prelude::ReactionType;
+use serde::{Deserialize, Serialize};
 
 #[non_exhaustive]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Entry {
 	Attack(ReactionType, String, String, usize),
-	Critical(String, String, usize),
+	Critical(ReactionType, String, String, usize, usize),
+	Miss(ReactionType, String, String),
+	Lifesteal(String, usize),
+	Thorns(String, String, usize),
 	Surrender(String),
 	Timeout(String),
+	Forfeit(String, usize),
+	Defend(String, usize),
 	Item(ReactionType, String),
+	StatusDamage(StatusKind, String, usize),
+	StatusHeal(StatusKind, String, usize),
+	StatusExpired(StatusKind, String),
+	Stunned(String),
+	WeaponBroke(ReactionType, String, String),
+	Switch(ReactionType, String, String),
+	SuddenDeath,
+	SuddenDeathDamage(String, usize),
 }
 
 impl Display for Entry {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let entry = match self {
 			Entry::Attack(icon, p1, p2, damage) => format!("{icon} {p1} attacked {p2} for {damage} damage."),
-			Entry::Critical(p1, p2, damage) => format!("💥 {p1} got a critical hit on {p2} for {damage} damage!"),
+			Entry::Critical(icon, p1, p2, damage, 0) => format!("💥 {icon} {p1} got a critical hit on {p2} for {damage} damage!"),
+			Entry::Critical(icon, p1, p2, damage, bypassed) => format!("💥 {icon} {p1} got a critical hit on {p2} for {damage} damage, bypassing {bypassed} armor!"),
+			Entry::Miss(icon, p1, p2) => format!("{icon} {p1} attacked {p2}, but missed."),
+			Entry::Lifesteal(player, healing) => format!("🧛 {player} drained {healing} health."),
+			Entry::Thorns(p1, p2, damage) => format!("🌵 {p2} took {damage} damage from {p1}'s thorns."),
 			Entry::Surrender(player) => format!("🏳 {player} surrendered."),
 			Entry::Timeout(player) => format!("🕑 {player} took too long."),
+			Entry::Forfeit(player, timeouts) => format!("🏳 {player} forfeited after taking too long {timeouts} turns in a row."),
+			Entry::Defend(player, armor) => format!("🛡 {player} raised their guard, gaining {armor} armor until their next turn."),
 			Entry::Item(icon, str) => format!("{icon} {str}"),
+			Entry::StatusDamage(status, player, damage) => format!("{} {player} took {damage} {status} damage.", status.icon()),
+			Entry::StatusHeal(status, player, healing) => format!("{} {player} recovered {healing} health from {status}.", status.icon()),
+			Entry::StatusExpired(status, player) => format!("{} {player}'s {status} wore off.", status.icon()),
+			Entry::Stunned(player) => format!("💫 {player} is stunned and can't act."),
+			Entry::WeaponBroke(icon, player, weapon) => format!("{icon} {player}'s {weapon} broke! They're fighting with their fists now."),
+			Entry::Switch(icon, player, weapon) => format!("🔄 {player} switched to their {icon} {weapon}."),
+			Entry::SuddenDeath => "☠ Sudden death! Nobody can heal, and armor wears away every turn.".to_string(),
+			Entry::SuddenDeathDamage(player, damage) => format!("☠ {player} took {damage} sudden death damage."),
 		};
 
 		write!(f, "{}", entry)
 	}
 }
 
-#[derive(Clone)]
-pub struct Log(Vec<Entry>);
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Log {
+	entries: Vec<Entry>,
+	events: Vec<Event>,
+}
 
 impl Log {
 	pub const fn new() -> Self {
-		Self(vec![])
+		Self {
+			entries: vec![],
+			events: vec![],
+		}
 	}
 
 	pub fn add(&mut self, entry: Entry) {
-		self.0.push(entry);
+		self.events.push(Event::Entry(entry.to_string()));
+		self.entries.push(entry);
+	}
+
+	/// Records an event for the replay without showing it in the log.
+	pub fn record(&mut self, event: Event) {
+		self.events.push(event);
+	}
+
+	pub fn events(&self) -> &[Event] {
+		&self.events
 	}
 
 	pub fn get_last_entries(&self, n: usize) -> Option<Vec<&Entry>> {
-		if self.0.is_empty() {
+		if self.entries.is_empty() {
 			None
 		} else {
-			Some(self.0.iter().rev().take(n).collect())
+			Some(self.entries.iter().rev().take(n).collect())
 		}
 	}
 }
@@ -50,7 +96,7 @@ impl Display for Log {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		const MAX_ENTRIES: usize = 30;
 
-		let log = self.0.iter().rev().take(MAX_ENTRIES).fold(String::new(), |acc, entry| format!("{}\n{}", acc, entry));
+		let log = self.entries.iter().rev().take(MAX_ENTRIES).fold(String::new(), |acc, entry| format!("{}\n{}", acc, entry));
 		write!(f, "{}", log)
 	}
 }
\ No newline at end of file
@@ -1,172 +1,942 @@
+mod ai;
+mod armory;
+mod damage;
+mod engine;
+mod item;
 mod log;
+mod npc;
 mod player;
+mod rarity;
+mod replay;
+mod rng;
+mod script;
+mod skill;
+mod snapshot;
+mod state;
+mod status;
 mod util;
+mod weapon;
 
+pub use ai::{Balanced, Strategy};
+pub use armory::{Armory, Loadout};
+pub use damage::DamageType;
+pub use engine::{Action, BattleEngine, DEFAULT_MAX_TIMEOUTS, Frontend, Outcome, TurnOrder};
+pub use item::Item;
+pub use npc::NpcBattler;
 pub use player::Player;
+pub use rarity::{Rarity, RarityWeights};
+pub use replay::Replay;
+pub use rng::BattleRng;
+pub use skill::Skill;
+pub use state::BattlerState;
+pub use status::{Status, StatusKind};
+pub use weapon::Weapon;
 
 use crate::{prelude::*, util::base_embed};
-use log::Log;
-use player::create_battle_embed;
-use util::{BattlerInfo, create_invite_action_row};
+use log::{Entry, Log};
+use snapshot::{BattlerRef, SavedBattle};
+use util::{BattlerDisplay, BattlerInfo, create_battle_components, create_battle_embed, create_invite_action_row, create_rematch_action_row};
 
-use async_trait::async_trait;
-use poise::{
-	ReplyHandle,
-	serenity_prelude::{User, UserId},
+use std::{
+	collections::HashMap,
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use async_trait::async_trait;
+use futures::future::join_all;
+use poise::serenity_prelude::{self as serenity, ButtonStyle, GuildId, Message, User, UserId};
 use uuid::Uuid;
 
-#[async_trait]
+/// The highest chance a battler can have of dodging an attack, in percent.
+const MAX_EVASION: usize = 75;
+/// How long invited users have to accept a battle.
+const INVITE_TIMEOUT: Duration = Duration::from_secs(120);
+/// How long a rematch can be asked for after a battle.
+const REMATCH_TIMEOUT: Duration = Duration::from_secs(120);
+
 pub trait Battler: Send + Sync {
 	fn user_id(&self) -> Option<UserId> { None }
 	fn id(&self) -> &Uuid;
 	fn name(&self) -> &String;
 	fn icon(&self) -> Option<String> { None }
 
-	async fn act(&mut self, battle: &mut Battle, opponent: &mut dyn Battler) -> Result<(), Error>;
+	/// The battler's stats and equipment, which the methods below read and change.
+	fn state(&self) -> &BattlerState;
+	fn state_mut(&mut self) -> &mut BattlerState;
+
+	fn health(&self) -> usize { self.state().health }
+	fn max_health(&self) -> usize { self.state().max_health }
+	fn armor(&self) -> usize { self.state().armor }
+	fn speed(&self) -> usize { self.state().speed }
+	/// The base chance of dodging attacks, in percent.
+	fn evasion(&self) -> usize { self.state().evasion }
+	/// How much of the damage the battler deals heals it, in percent.
+	fn lifesteal(&self) -> usize { self.state().lifesteal }
+	/// How much of the damage the battler takes from attackers is returned to them, in percent.
+	fn thorns(&self) -> usize { self.state().thorns }
+	fn energy(&self) -> usize { self.state().energy }
+	fn max_energy(&self) -> usize { self.state().max_energy }
+
+	fn set_health(&mut self, health: usize) {
+		let state = self.state_mut();
+		state.health = health.min(state.max_health);
+	}
+	fn set_armor(&mut self, armor: usize) { self.state_mut().armor = armor; }
+	fn set_speed(&mut self, speed: usize) { self.state_mut().speed = speed; }
+	fn set_evasion(&mut self, evasion: usize) { self.state_mut().evasion = evasion; }
+	fn set_lifesteal(&mut self, lifesteal: usize) { self.state_mut().lifesteal = lifesteal; }
+	fn set_thorns(&mut self, thorns: usize) { self.state_mut().thorns = thorns; }
+	fn set_energy(&mut self, energy: usize) {
+		let state = self.state_mut();
+		state.energy = energy.min(state.max_energy);
+	}
+
+	fn weapon(&self) -> &Weapon { &self.state().weapon }
+	fn weapon_mut(&mut self) -> &mut Weapon { &mut self.state_mut().weapon }
+	fn secondary_weapon(&self) -> Option<&Weapon> { self.state().secondary.as_ref() }
+	/// Swaps the active weapon with the secondary one, returning whether there was one.
+	fn switch_weapons(&mut self) -> bool {
+		let state = self.state_mut();
+		if let Some(secondary) = &mut state.secondary {
+			std::mem::swap(&mut state.weapon, secondary);
+			true
+		} else {
+			false
+		}
+	}
+	/// The battler's items, keyed by their IDs in the database.
+	fn items(&self) -> &HashMap<i32, Item> { &self.state().items }
+	fn items_mut(&mut self) -> &mut HashMap<i32, Item> { &mut self.state_mut().items }
+
+	fn skills(&self) -> &[Skill] { &self.state().skills }
+	/// How many more of this battler's turns each skill has to wait before it can be used again.
+	fn cooldowns(&self) -> &HashMap<Uuid, usize> { &self.state().cooldowns }
+	fn cooldowns_mut(&mut self) -> &mut HashMap<Uuid, usize> { &mut self.state_mut().cooldowns }
+
+	fn statuses(&self) -> &[Status] { &self.state().statuses }
+	fn statuses_mut(&mut self) -> &mut Vec<Status> { &mut self.state_mut().statuses }
+
+	/// How much less damage of each type the battler takes, in percent. Negative resistances are weaknesses.
+	fn resistances(&self) -> &HashMap<DamageType, isize> { &self.state().resistances }
+	fn resistances_mut(&mut self) -> &mut HashMap<DamageType, isize> { &mut self.state_mut().resistances }
 
-	fn health(&self) -> usize;
-	fn max_health(&self) -> usize;
-	fn armor(&self) -> usize;
+	/// The strategy that chooses this battler's actions, if it isn't controlled by a user.
+	fn strategy(&self) -> Option<&dyn Strategy> { None }
 
-	fn set_health(&mut self, health: usize);
-	fn set_armor(&mut self, armor: usize);
+	/// A fresh copy of this battler with the given loadout, for a rematch.
+	fn rematch(&self, loadout: Loadout) -> Box<dyn Battler>;
 
-	fn info(&self) -> BattlerInfo;
+	fn snapshot(&self) -> BattlerRef<'_>;
 }
 
 impl<'a> dyn Battler + 'a {
-	fn damage(&mut self, damage: usize, pierce: usize) -> usize {
+	fn damage(&mut self, damage: usize, pierce: usize, damage_type: DamageType) -> usize {
+		let damage = match damage_type {
+			DamageType::True => damage,
+			_ => damage::apply_resistance(damage, self.resistance(damage_type)),
+		};
+		let damage = match self.status(StatusKind::Guard) {
+			Some(guard) => damage.saturating_sub(damage.saturating_mul(guard.potency.min(100)) / 100),
+			None => damage,
+		};
 		let damage = damage.saturating_sub(self.armor().saturating_sub(pierce)).min(self.health());
 		self.set_health(self.health() - damage);
 		damage
 	}
+	/// Damages a target on behalf of this battler, which heals from its lifesteal and takes damage from the target's thorns.
+	fn strike(&mut self, target: &mut dyn Battler, damage: usize, pierce: usize, damage_type: DamageType, log: &mut Log) -> usize {
+		let damage = target.damage(damage, pierce, damage_type);
+
+		let healing = self.heal(damage.saturating_mul(self.total_lifesteal()) / 100);
+		if healing > 0 {
+			log.add(Entry::Lifesteal(self.name().clone(), healing));
+		}
+
+		// Reflected damage ignores armor and resistances, and can't be reflected back.
+		let reflected = damage.saturating_mul(target.total_thorns()) / 100;
+		if reflected > 0 && self.health() > 0 {
+			let reflected = self.damage(reflected, usize::MAX, DamageType::True);
+			log.add(Entry::Thorns(target.name().clone(), self.name().clone(), reflected));
+		}
+
+		damage
+	}
+
+	/// Lifesteal, counting the active weapon.
+	fn total_lifesteal(&self) -> usize {
+		self.lifesteal().saturating_add(self.weapon().lifesteal)
+	}
+
+	/// Thorns, counting the active weapon.
+	fn total_thorns(&self) -> usize {
+		self.thorns().saturating_add(self.weapon().thorns)
+	}
+
+	fn add_lifesteal(&mut self, lifesteal: isize) {
+		self.set_lifesteal((self.lifesteal() as isize).saturating_add(lifesteal).max(0) as usize);
+	}
+
+	fn add_thorns(&mut self, thorns: isize) {
+		self.set_thorns((self.thorns() as isize).saturating_add(thorns).max(0) as usize);
+	}
+
 	fn heal(&mut self, healing: usize) -> usize {
+		if self.status(StatusKind::Wounded).is_some() {
+			return 0;
+		}
+
 		let healing = healing.min(self.max_health() - self.health());
 		self.set_health(self.health() + healing);
 		healing
 	}
 
+	fn resistance(&self, damage_type: DamageType) -> isize {
+		self.resistances().get(&damage_type).copied().unwrap_or(0)
+	}
+
+	/// Adds to a resistance, which stays between the strongest weakness and the strongest resistance.
+	fn add_resistance(&mut self, damage_type: DamageType, resistance: isize) {
+		let resistance = self.resistance(damage_type).saturating_add(resistance).clamp(damage::MAX_WEAKNESS, damage::MAX_RESISTANCE);
+		self.resistances_mut().insert(damage_type, resistance);
+	}
+
 	fn add_armor(&mut self, armor: usize) {
 		self.set_armor(self.armor().saturating_add(armor));
 	}
+
+	fn info(&self) -> BattlerInfo {
+		let weapon = self.weapon();
+		BattlerInfo {
+			name: self.name().clone(),
+			icon: self.icon(),
+			health: self.health(),
+			max_health: self.max_health(),
+			weapon: (weapon.icon.clone(), weapon.name.clone()),
+			weapon_rarity: weapon.rarity,
+			durability: weapon.durability.zip(weapon.max_durability),
+			secondary: self.secondary_weapon().map(|secondary| (secondary.icon.clone(), secondary.name.clone())),
+			energy: self.energy(),
+			max_energy: self.max_energy(),
+			armor: self.armor(),
+			speed: self.initiative(),
+			evasion: self.evasion_chance(),
+			lifesteal: self.total_lifesteal(),
+			thorns: self.total_thorns(),
+			statuses: self.statuses().to_vec(),
+			resistances: self.resistances().iter().map(|(&damage_type, &resistance)| (damage_type, resistance)).collect(),
+		}
+	}
+
+	/// How quickly the battler's turns come around, counting its weapon.
+	fn initiative(&self) -> usize {
+		(self.speed() as isize).saturating_add(self.weapon().speed).max(1) as usize
+	}
+
+	fn add_energy(&mut self, energy: isize) {
+		self.set_energy((self.energy() as isize).saturating_add(energy).max(0) as usize);
+	}
+
+	/// Whether the battler has enough energy for a skill and it isn't cooling down.
+	fn can_cast(&self, skill: &Skill) -> bool {
+		self.energy() >= skill.cost && self.cooldowns().get(&skill.id).is_none_or(|&cooldown| cooldown == 0)
+	}
+
+	/// The chance of dodging attacks in percent, counting evasion statuses.
+	fn evasion_chance(&self) -> usize {
+		let bonus = self.status(StatusKind::Evasion).map_or(0, |status| status.potency.saturating_mul(status.stacks));
+		self.evasion().saturating_add(bonus).min(MAX_EVASION)
+	}
+
+	fn add_evasion(&mut self, evasion: isize) {
+		self.set_evasion((self.evasion() as isize).saturating_add(evasion).max(0) as usize);
+	}
+
+	fn add_speed(&mut self, speed: isize) {
+		self.set_speed((self.speed() as isize).saturating_add(speed).max(0) as usize);
+	}
+
+	/// Takes one of an item, removing its stack once it runs out.
+	fn take_item(&mut self, id: i32) -> Option<Item> {
+		let items = self.items_mut();
+		let stack = items.get_mut(&id)?;
+		stack.quantity = stack.quantity.saturating_sub(1);
+		let item = Item { quantity: 1, ..stack.clone() };
+		if stack.quantity == 0 {
+			items.remove(&id);
+		}
+		Some(item)
+	}
+
+	fn status(&self, kind: StatusKind) -> Option<&Status> {
+		self.statuses().iter().find(|status| status.kind == kind)
+	}
+
+	fn remove_status(&mut self, kind: StatusKind) -> Option<Status> {
+		let statuses = self.statuses_mut();
+		let index = statuses.iter().position(|status| status.kind == kind)?;
+		let status = statuses.remove(index);
+		self.set_armor(self.armor().saturating_sub(status.armor));
+		Some(status)
+	}
+
+	/// Applies a status, stacking it with any status of the same kind.
+	///
+	/// Armor from stacked statuses doesn't add up, so the battler only gains what the new status grants beyond the old one.
+	fn apply_status(&mut self, status: Status) {
+		if status.turns == 0 || self.health() == 0 {
+			return;
+		}
+
+		let statuses = self.statuses_mut();
+		let armor = if let Some(existing) = statuses.iter_mut().find(|existing| existing.kind == status.kind) {
+			let armor = status.armor.saturating_sub(existing.armor);
+			existing.stacks = (existing.stacks + status.stacks).min(status::MAX_STACKS);
+			existing.turns = existing.turns.max(status.turns);
+			existing.potency = existing.potency.max(status.potency);
+			existing.armor = existing.armor.max(status.armor);
+			armor
+		} else {
+			let armor = status.armor;
+			statuses.push(status);
+			armor
+		};
+		self.add_armor(armor);
+	}
 }
 
+async fn battler_displays(discord: &serenity::Context, guild_id: GuildId, engine: &BattleEngine) -> Vec<BattlerDisplay> {
+	let mut displays = vec![];
+	for (i, battler) in engine.battlers().iter().enumerate() {
+		let display = battler.info().display(discord, guild_id).await;
+		displays.push(if engine.has_teams() { display.with_team(engine.team(i)) } else { display });
+	}
+	displays
+}
+
+/// The Discord frontend of a battle.
+///
+/// It only needs the message the battle is shown in, so it can pick a battle back up without a command.
 pub struct Battle<'a> {
 	id: Uuid,
-	ctx: Context<'a>,
-	reply: ReplyHandle<'a>,
-	p1_turn: bool,
-	log: Log,
+	discord: &'a serenity::Context,
+	data: &'a Data,
+	/// Keeps the battle's users registered until the battle is dropped.
+	reservation: Reservation<'a>,
+	guild_id: GuildId,
+	message: Message,
+	/// When the current user's turn runs out.
+	deadline: SystemTime,
 }
 
 impl<'a> Battle<'a> {
-	fn new(ctx: Context<'a>, reply: ReplyHandle<'a>) -> Self {
+	fn new(discord: &'a serenity::Context, data: &'a Data, guild_id: GuildId, message: Message, reservation: Reservation<'a>) -> Self {
 		Self {
-			id: Uuid::new_v4(),
-			ctx,
-			reply,
-			p1_turn: rand::random(),
-			log: Log::new(),
+			id: *reservation.id(),
+			discord,
+			data,
+			reservation,
+			guild_id,
+			message,
+			deadline: SystemTime::now(),
 		}
 	}
 
-	pub async fn send_invite(ctx: Context<'a>, u1: User, u2: User) -> Result<(), Error> {
-		let mut p1 = Player::new(u1, ctx, true)?;
-		let mut p2 = Player::new(u2, ctx, false)?;
+	fn into_message(self) -> Message {
+		self.message
+	}
+
+	/// How long the current user has left to act.
+	fn remaining(&self) -> Duration {
+		self.deadline.duration_since(SystemTime::now()).unwrap_or_default()
+	}
+
+	/// Invites everyone but the first member of the first team to a battle, which starts once they have all accepted.
+	pub async fn send_invite(ctx: Context<'a>, teams: Vec<Vec<User>>, turn_order: TurnOrder) -> Result<(), Error> {
+		let guild_id = ctx.guild_id().ok_or("No guild found.")?;
+		let armory = Armory::load()?.with_weights(ctx.data().config.rarity_weights);
+		let mut rng = BattleRng::new();
+		let mut players: Vec<Vec<Player>> = vec![];
+		for team in teams {
+			let mut members = vec![];
+			for user in team {
+				members.push(Player::new(user, &armory, &mut rng)?);
+			}
+			players.push(members);
+		}
+
+		let has_teams = players.iter().any(|team| team.len() > 1);
+		let mut displays = vec![];
+		for (team, members) in players.iter().enumerate() {
+			for player in members {
+				let display = (player as &dyn Battler).info().display(ctx.discord(), guild_id).await;
+				displays.push(if has_teams { display.with_team(team) } else { display });
+			}
+		}
+
+		let mentions = |players: &[Player], separator: &str| players
+			.iter()
+			.map(Player::mention)
+			.collect::<Vec<_>>()
+			.join(separator);
+		let (title, description) = if has_teams {
+			let opponents = players[1..].iter().map(|team| mentions(team.as_slice(), " & ")).collect::<Vec<_>>().join(" and ");
+			("⚔ Team Battle Invitation", format!("{} challenged {} to a team battle!", mentions(players[0].as_slice(), " & "), opponents))
+		} else if players.len() > 2 {
+			let opponents = players[1..].iter().map(|team| mentions(team.as_slice(), ", ")).collect::<Vec<_>>().join(", ");
+			("⚔ Free-for-All Invitation", format!("{} challenged {} to a free-for-all!", mentions(players[0].as_slice(), ", "), opponents))
+		} else {
+			("⚔ Duel Invitation", format!("{} challenged {} to a duel!", mentions(players[0].as_slice(), ", "), mentions(players[1].as_slice(), ", ")))
+		};
 
-		let p1_display = p1.info().display().await;
-		let p2_display = p2.info().display().await;
+		let users: Vec<&User> = players.iter().flatten().map(Player::user).collect();
+		let pending: Vec<UserId> = users[1..].iter().map(|user| user.id).collect();
+		let ids = users.iter().map(|user| user.id).collect();
+		let names: HashMap<UserId, String> = users.iter().map(|user| (user.id, user.name.clone())).collect();
+
+		let teams = players
+			.into_iter()
+			.map(|team| team.into_iter().map(|player| Box::new(player) as Box<dyn Battler>).collect())
+			.collect();
+		let engine = BattleEngine::new(teams, turn_order, rng);
+
+		let reservation = match ctx.data().battles.reserve(*engine.id(), guild_id, ctx.channel_id(), ids) {
+			Ok(reservation) => reservation,
+			Err(user) => {
+				ctx.send(|m| m.content(format!("{} is currently in a battle. Try again later.", names[&user])).ephemeral(true)).await?;
+				return Ok(());
+			},
+		};
 
 		let reply = ctx.send(|m|
-			m.embed(|e| create_battle_embed(e, &p1_display, &p2_display, true, &Log::new())
-				.title("⚔ Duel Invitation")
-				.description(format!("{} challenged {} to a duel!", &p1.mention(), &p2.mention()))
+			m.embed(|e| create_battle_embed(e, &displays, 0, &Log::new(), None)
+				.title(title)
+				.description(&description)
 			).components(|c| create_invite_action_row(c, false))
 		).await?;
+		let message = reply.message().await?.into_owned();
+		reservation.set_message(message.id);
 
-		let interaction = reply
-			.message()
-			.await?
-			.await_component_interaction(ctx.discord())
-			.author_id(p2.user().id)
-			.await;
+		if !Battle::await_acceptance(ctx.discord(), &message, pending).await? {
+			return Ok(());
+		}
 
-		if let Some(m) = interaction {
-			m.defer(ctx.discord()).await?;
+		Battle::play(ctx.discord(), ctx.data(), guild_id, message, reservation, engine).await
+	}
 
-			match &*m.data.custom_id {
-				"fight" => {
-					if ctx.data().check_for_user_in_battle(p2.user()) {
-						reply.edit(ctx, |m| m.components(|c| c)).await?;
-						ctx.send(|c| c.content("You cannot be in two battles at once.").ephemeral(true)).await?;
-						return Ok(());
-					}
-					let mut battle = Battle::new(ctx, reply);
-					battle.start(&mut p1 as &mut dyn Battler, &mut p2 as &mut dyn Battler).await
+	/// Waits for every pending user to accept the invitation on `message`.
+	///
+	/// Returns whether they all did, letting the channel know otherwise.
+	async fn await_acceptance(discord: &serenity::Context, message: &Message, mut pending: Vec<UserId>) -> Result<bool, Error> {
+		while !pending.is_empty() {
+			let waiting = pending.clone();
+			let interaction = message
+				.await_component_interaction(discord)
+				.filter(move |m| waiting.contains(&m.user.id))
+				.timeout(INVITE_TIMEOUT)
+				.await;
+
+			if let Some(m) = interaction {
+				m.defer(discord).await?;
+
+				match &*m.data.custom_id {
+					"fight" => pending.retain(|id| *id != m.user.id),
+					"run" => {
+						message.channel_id.edit_message(discord, message.id, |m| m.components(|c| c)).await?;
+						message.reply(discord, format!("<@{}> ran away.", m.user.id)).await?;
+						return Ok(false);
+					},
+					other => return Err(format!("Unknown button ID {other}.").into()),
 				}
-				"run" => {
-					reply.edit(ctx, |m| m.components(|c| c)).await?;
-					reply.message().await?.reply(ctx.discord(), format!("{} ran away.", p2.mention())).await?;
-					Ok(())
+			} else {
+				message.channel_id.edit_message(discord, message.id, |m| m.components(|c| c)).await?;
+				message.reply(discord, "The invitation timed out.").await?;
+				return Ok(false);
+			}
+		}
+
+		Ok(true)
+	}
+
+	pub async fn send_fight(ctx: Context<'a>, user: User) -> Result<(), Error> {
+		let guild_id = ctx.guild_id().ok_or("No guild found.")?;
+		let armory = Armory::load()?.with_weights(ctx.data().config.rarity_weights);
+		let mut rng = BattleRng::new();
+		let player = Player::new(user, &armory, &mut rng)?;
+
+		let bot = ctx.discord().cache.current_user();
+		let npc = NpcBattler::new(bot.name.clone(), bot.avatar_url(), Box::new(Balanced::default()), &armory, &mut rng)?;
+
+		let description = format!("{} picked a fight with {}!", player.mention(), npc.name());
+
+		let teams = vec![
+			vec![Box::new(player) as Box<dyn Battler>],
+			vec![Box::new(npc) as Box<dyn Battler>],
+		];
+		let engine = BattleEngine::new(teams, TurnOrder::Alternating, rng);
+
+		let reservation = match ctx.data().battles.reserve(*engine.id(), guild_id, ctx.channel_id(), vec![ctx.author().id]) {
+			Ok(reservation) => reservation,
+			Err(_) => {
+				ctx.send(|m| m.content("You cannot be in two battles at once.").ephemeral(true)).await?;
+				return Ok(());
+			},
+		};
+
+		let reply = ctx.send(|m|
+			m.embed(|e| base_embed(e)
+				.title("⚔ Fight")
+				.description(&description)
+			)
+		).await?;
+		let message = reply.message().await?.into_owned();
+		reservation.set_message(message.id);
+
+		Battle::play(ctx.discord(), ctx.data(), guild_id, message, reservation, engine).await
+	}
+
+	/// Picks every saved battle that isn't running back up, such as after a restart.
+	///
+	/// Battles that can't be picked back up lose their buttons, so nobody is left clicking them.
+	pub async fn resume_all(discord: &'a serenity::Context, data: &'a Data) -> Result<(), Error> {
+		let (saved, unreadable) = SavedBattle::load_all()?;
+		for battle in unreadable {
+			if let Err(e) = battle.channel_id.edit_message(discord, battle.message_id, |m| m.components(|c| c)).await {
+				eprintln!("{:?}", e);
+			}
+		}
+
+		let saved: Vec<SavedBattle> = saved
+			.into_iter()
+			.filter(|saved| !data.battles.contains(saved.engine.id()))
+			.collect();
+
+		let results = join_all(saved.into_iter().map(|saved| Battle::resume(discord, data, saved))).await;
+		for result in results {
+			if let Err(e) = result {
+				eprintln!("{:?}", e);
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn resume(discord: &'a serenity::Context, data: &'a Data, saved: SavedBattle) -> Result<(), Error> {
+		let message = saved.channel_id.message(discord, saved.message_id).await?;
+		let engine = BattleEngine::restore(saved.engine);
+
+		let ids = engine.battlers().iter().filter_map(|battler| battler.user_id()).collect();
+		let reservation = data.battles
+			.reserve(*engine.id(), saved.guild_id, saved.channel_id, ids)
+			.map_err(|user| format!("Could not resume battle {} because <@{}> is in another battle.", engine.id(), user))?;
+		reservation.set_message(message.id);
+
+		Battle::play(discord, data, saved.guild_id, message, reservation, engine).await
+	}
+
+	/// Runs a battle, followed by any rematches its users agree to.
+	async fn play(discord: &'a serenity::Context, data: &'a Data, guild_id: GuildId, mut message: Message, mut reservation: Reservation<'a>, mut engine: BattleEngine) -> Result<(), Error> {
+		loop {
+			let mut battle = Battle::new(discord, data, guild_id, message, reservation);
+			battle.start(&mut engine).await?;
+
+			// This drops the battle's reservation, freeing its users while they decide on a rematch.
+			let result = battle.into_message();
+			match Battle::rematch(discord, data, guild_id, &result, &engine).await? {
+				Some((next_message, next_reservation, next_engine)) => {
+					message = next_message;
+					reservation = next_reservation;
+					engine = next_engine;
 				},
-				other => Err(format!("Unknown button ID {other}.").into()),
+				None => return Ok(()),
 			}
+		}
+	}
+
+	/// Offers a rematch under the result of a finished battle, and sets it up once everyone has accepted.
+	///
+	/// Whoever asks for the rematch picks between new loadouts and the ones the battle started with.
+	async fn rematch(discord: &'a serenity::Context, data: &'a Data, guild_id: GuildId, result: &Message, engine: &BattleEngine) -> Result<Option<(Message, Reservation<'a>, BattleEngine)>, Error> {
+		let users: Vec<UserId> = engine.battlers().iter().filter_map(|battler| battler.user_id()).collect();
+		let waiting = users.clone();
+		let interaction = result
+			.await_component_interaction(discord)
+			.filter(move |m| waiting.contains(&m.user.id))
+			.timeout(REMATCH_TIMEOUT)
+			.await;
+
+		result.channel_id.edit_message(discord, result.id, |m| m.components(|c| c)).await?;
+
+		let m = if let Some(m) = interaction {
+			m
 		} else {
-			ctx.say("The invitation timed out.").await?;
-			Ok(())
+			return Ok(None);
+		};
+		m.defer(discord).await?;
+
+		let mut rng = BattleRng::new();
+		let (loadouts, mode) = match &*m.data.custom_id {
+			"rematch" => {
+				let armory = Armory::load()?.with_weights(data.config.rarity_weights);
+				let loadouts = engine
+					.battlers()
+					.iter()
+					.map(|_| armory.draw_loadout(&mut rng))
+					.collect::<Result<Vec<_>, _>>()?;
+				(loadouts, "new loadouts")
+			},
+			"rematch_same" => {
+				if engine.loadouts().len() != engine.battlers().len() {
+					return Err("The starting loadouts of this battle are unknown.".into());
+				}
+				(engine.loadouts().to_vec(), "the same loadouts")
+			},
+			other => return Err(format!("Unknown button ID {other}.").into()),
+		};
+
+		let next = BattleEngine::new(engine.rematch_teams(loadouts), engine.turn_order(), rng);
+		let reservation = match data.battles.reserve(*next.id(), guild_id, result.channel_id, users.clone()) {
+			Ok(reservation) => reservation,
+			Err(user) => {
+				result.reply(discord, format!("<@{user}> is currently in a battle, so there can't be a rematch.")).await?;
+				return Ok(None);
+			},
+		};
+
+		let displays = battler_displays(discord, guild_id, &next).await;
+		let pending: Vec<UserId> = users.into_iter().filter(|id| *id != m.user.id).collect();
+		let message = result.channel_id.send_message(discord, |msg|
+			msg.embed(|e| create_battle_embed(e, &displays, 0, &Log::new(), None)
+				.title("🔁 Rematch")
+				.description(format!("<@{}> wants a rematch with {mode}!", m.user.id))
+			).components(|c| if pending.is_empty() { c } else { create_invite_action_row(c, false) })
+		).await?;
+		reservation.set_message(message.id);
+
+		if !Battle::await_acceptance(discord, &message, pending).await? {
+			return Ok(None);
+		}
+
+		Ok(Some((message, reservation, next)))
+	}
+
+	async fn start(&mut self, engine: &mut BattleEngine) -> Result<(), Error> {
+		engine.set_max_timeouts(self.data.config.max_timeouts);
+		engine.set_free_switch(self.data.config.free_switch);
+		self.reservation.start();
+		let result = engine.run(self).await;
+
+		// A battle that failed partway through stays saved, so it can be picked back up. A finished one is cleared
+		// even if showing its result failed, or it would be picked back up on every restart.
+		if engine.is_over() {
+			if let Err(e) = engine.replay().save() {
+				eprintln!("{:?}", e);
+			}
+			if let Err(e) = SavedBattle::delete(&self.id) {
+				eprintln!("{:?}", e);
+			}
 		}
+
+		result
 	}
 
-	async fn start(&mut self, p1: &mut dyn Battler, p2: &mut dyn Battler) -> Result<(), Error> {
-		let (p1_id, p2_id) = (p1.user_id(), p2.user_id());
-		self.ctx.data().battles.write().unwrap().insert(self.id, vec![p1_id, p2_id]);
-		self.battle_loop(p1, p2).await?;
+	async fn displays(&self, engine: &BattleEngine) -> Vec<BattlerDisplay> {
+		battler_displays(self.discord, self.guild_id, engine).await
+	}
+
+	async fn render(&self, engine: &BattleEngine, disabled: bool) -> Result<(), Error> {
+		let displays = self.displays(engine).await;
+		let battler = engine.current();
+		let disable_items = battler.items().is_empty();
+		let disable_skills = !battler.skills().iter().any(|skill| battler.can_cast(skill));
+		let disable_switch = !engine.can_switch();
+		let deadline = self.deadline.duration_since(UNIX_EPOCH)?.as_secs();
+
+		self.message.channel_id.edit_message(self.discord, self.message.id, |m|
+			m.embed(|e| create_battle_embed(e, &displays, engine.turn(), engine.log(), Some(deadline)))
+				.components(|c| create_battle_components(c, disabled, disable_items, disable_skills, disable_switch))
+		).await?;
+
 		Ok(())
 	}
 
-	async fn battle_loop(&mut self, p1: &mut dyn Battler, p2: &mut dyn Battler) -> Result<(), Error> {
-		while p1.health() > 0 && p2.health() > 0 {
-			if self.p1_turn {
-				p1.act(self, p2).await?;
+	async fn select_item(&self, battler: &dyn Battler, user_id: UserId) -> Result<Option<i32>, Error> {
+		let message = self.message.channel_id.send_message(self.discord, |m|
+			m.content("Select an item:")
+				.components(|c|
+					c.create_action_row(|r|
+						r.create_select_menu(|m|
+							m.custom_id("item")
+								.placeholder("Select an item...")
+								.options(|o| battler.items().values().fold(o, |acc, item| acc.create_option(|o| item.as_option(o))))
+						)
+					).create_action_row(|r|
+						r.create_button(|b|
+							b.custom_id("back")
+								.emoji('◀')
+								.label("Back")
+								.style(ButtonStyle::Danger)
+						)
+					)
+				)
+		).await?;
+
+		let interaction = message
+			.await_component_interaction(self.discord)
+			.author_id(user_id)
+			.timeout(self.remaining())
+			.await;
+
+		message.delete(self.discord).await?;
+
+		if let Some(m) = interaction {
+			m.defer(self.discord).await?;
+
+			match &*m.data.custom_id {
+				"item" => Ok(Some(m.data.values.first().ok_or("No values received.")?.parse()?)),
+				"back" => Ok(None),
+				other => Err(format!("Unknown ID {other}.").into()),
+			}
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Lets the user pick one of the skills they can currently cast.
+	async fn select_skill(&self, battler: &dyn Battler, user_id: UserId) -> Result<Option<Uuid>, Error> {
+		let message = self.message.channel_id.send_message(self.discord, |m|
+			m.content("Select a skill:")
+				.components(|c|
+					c.create_action_row(|r|
+						r.create_select_menu(|m|
+							m.custom_id("skill")
+								.placeholder("Select a skill...")
+								.options(|o| battler
+									.skills()
+									.iter()
+									.filter(|skill| battler.can_cast(skill))
+									.fold(o, |acc, skill| acc.create_option(|o| skill.as_option(o)))
+								)
+						)
+					).create_action_row(|r|
+						r.create_button(|b|
+							b.custom_id("back")
+								.emoji('◀')
+								.label("Back")
+								.style(ButtonStyle::Danger)
+						)
+					)
+				)
+		).await?;
+
+		let interaction = message
+			.await_component_interaction(self.discord)
+			.author_id(user_id)
+			.timeout(self.remaining())
+			.await;
+
+		message.delete(self.discord).await?;
+
+		if let Some(m) = interaction {
+			m.defer(self.discord).await?;
+
+			match &*m.data.custom_id {
+				"skill" => Ok(Some(Uuid::parse_str(m.data.values.first().ok_or("No values received.")?)?)),
+				"back" => Ok(None),
+				other => Err(format!("Unknown ID {other}.").into()),
+			}
+		} else {
+			Ok(None)
+		}
+	}
+
+	/// Asks the user who to target, skipping the menu if there is only one choice.
+	async fn select_targets(&self, engine: &BattleEngine, user_id: UserId, area: bool) -> Result<Option<Vec<usize>>, Error> {
+		let targets = engine.targets();
+		if targets.len() <= 1 {
+			return Ok(Some(targets));
+		}
+
+		self.message.channel_id.edit_message(self.discord, self.message.id, |m|
+			m.components(|c| create_battle_components(c, true, true, true, true))
+		).await?;
+
+		let battlers = engine.battlers();
+		let message = self.message.channel_id.send_message(self.discord, |m|
+			m.content(if area { "Select targets:" } else { "Select a target:" })
+				.components(|c|
+					c.create_action_row(|r|
+						r.create_select_menu(|m| {
+							let m = m.custom_id("target")
+								.placeholder(if area { "Select targets..." } else { "Select a target..." })
+								.options(|o| targets.iter().fold(o, |acc, &target| acc.create_option(|o| {
+									let battler = &battlers[target];
+									o.label(battler.name())
+										.value(target)
+										.description(format!("❤ {}/{} 🛡 {}", battler.health(), battler.max_health(), battler.armor()))
+								})));
+
+							if area {
+								m.min_values(1).max_values(targets.len() as u64)
+							} else {
+								m
+							}
+						})
+					).create_action_row(|r|
+						r.create_button(|b|
+							b.custom_id("back")
+								.emoji('◀')
+								.label("Back")
+								.style(ButtonStyle::Danger)
+						)
+					)
+				)
+		).await?;
+
+		let interaction = message
+			.await_component_interaction(self.discord)
+			.author_id(user_id)
+			.timeout(self.remaining())
+			.await;
+
+		message.delete(self.discord).await?;
+
+		if let Some(m) = interaction {
+			m.defer(self.discord).await?;
+
+			match &*m.data.custom_id {
+				"target" => Ok(Some(m.data.values.iter().map(|value| value.parse()).collect::<Result<_, _>>()?)),
+				"back" => Ok(None),
+				other => Err(format!("Unknown ID {other}.").into()),
+			}
+		} else {
+			Ok(None)
+		}
+	}
+}
+
+#[async_trait]
+impl Frontend for Battle<'_> {
+	async fn choose_action(&mut self, engine: &BattleEngine) -> Result<Action, Error> {
+		let battler = engine.current();
+		let user_id = battler.user_id().ok_or("Only users can act in Discord battles.")?;
+		self.deadline = SystemTime::now() + self.data.config.turn_timeout;
+
+		loop {
+			self.render(engine, false).await?;
+
+			let interaction = self.message
+				.await_component_interaction(self.discord)
+				.author_id(user_id)
+				.timeout(self.remaining())
+				.await;
+
+			if let Some(m) = interaction {
+				m.defer(self.discord).await?;
+
+				match &*m.data.custom_id {
+					"attack" => {
+						if let Some(targets) = self.select_targets(engine, user_id, false).await? {
+							if let Some(&target) = targets.first() {
+								return Ok(Action::Attack { target });
+							}
+						}
+					},
+					"skill" => {
+						if !battler.skills().iter().any(|skill| battler.can_cast(skill)) {
+							continue;
+						}
+
+						self.message.channel_id.edit_message(self.discord, self.message.id, |m|
+							m.components(|c| create_battle_components(c, true, true, true, true))
+						).await?;
+
+						if let Some(skill_id) = self.select_skill(battler, user_id).await? {
+							let area = battler.skills().iter().find(|skill| skill.id == skill_id).is_some_and(|skill| skill.area);
+							if let Some(targets) = self.select_targets(engine, user_id, area).await? {
+								return Ok(Action::Skill { skill: skill_id, targets });
+							}
+						}
+					},
+					"switch" => {
+						if engine.can_switch() {
+							return Ok(Action::Switch);
+						}
+					},
+					"defend" => return Ok(Action::Defend),
+					"surrender" => return Ok(Action::Surrender),
+					"item" => {
+						if battler.items().is_empty() {
+							continue;
+						}
+
+						self.message.channel_id.edit_message(self.discord, self.message.id, |m|
+							m.components(|c| create_battle_components(c, true, true, true, true))
+						).await?;
+
+						if let Some(item_id) = self.select_item(battler, user_id).await? {
+							let area = battler.items().get(&item_id).is_some_and(|item| item.area);
+							if let Some(targets) = self.select_targets(engine, user_id, area).await? {
+								return Ok(Action::Item { item: item_id, targets });
+							}
+						}
+					},
+					other => return Err(format!("Unknown ID {other}.").into()),
+				}
 			} else {
-				p2.act(self, p1).await?;
-			};
+				return Ok(Action::Timeout);
+			}
+		}
+	}
 
-			self.p1_turn = !self.p1_turn;
+	async fn checkpoint(&mut self, engine: &BattleEngine) -> Result<(), Error> {
+		// Losing a save shouldn't end the battle.
+		if let Err(e) = SavedBattle::save(engine, self.guild_id, self.message.channel_id, self.message.id) {
+			eprintln!("{:?}", e);
 		}
 
-		let winner = p1.health() > 0 && p2.health() == 0 || p2.health() > 0 && p1.health() == 0;
-		let p1_win = winner && p2.health() == 0;
+		Ok(())
+	}
+
+	async fn finish(&mut self, engine: &BattleEngine) -> Result<(), Error> {
+		let log = engine.log();
+		let footer = format!("Seed: {} • Replay: {}", engine.seed(), engine.id());
+		let winners = engine.winners();
+		let same_loadouts = engine.loadouts().len() == engine.battlers().len();
+		let decision = match engine.outcome() {
+			Some(Outcome::Health(team)) => Some(format!("⌛ The turn limit was reached, so the battle went to whoever had the most health left ({:.0}%).", engine.health_percentage(team))),
+			Some(Outcome::Draw) => Some("⌛ The turn limit was reached with the leaders on equal health.".to_string()),
+			Some(Outcome::DoubleKnockout) => Some("💀 The last battlers standing were knocked out at the same time.".to_string()),
+			Some(Outcome::Knockout(_)) | None => None,
+		};
 
-		self.reply.edit(self.ctx, |m|
-			if winner {
+		self.message.channel_id.edit_message(self.discord, self.message.id, |m|
+			if let Some(winner) = winners.first() {
+				let names = winners.iter().map(|winner| winner.name().as_str()).collect::<Vec<_>>().join(" & ");
 				m.embed(|e| {
 					let e = base_embed(e)
-						.field("Log", &self.log, false);
-
-					if p1_win {
-						let e = e.title(format!("🏆 {} won!", p1.name()));
-						if let Some(url) = p1.icon() {
-							e.thumbnail(url)
-						} else {
-							e
-						}
+						.title(format!("🏆 {} won!", names))
+						.field("Log", log, false)
+						.footer(|f| f.text(&footer));
+					let e = if let Some(decision) = &decision { e.description(decision) } else { e };
+
+					if let Some(url) = winner.icon() {
+						e.thumbnail(url)
 					} else {
-						let e = e.title(format!("🏆 {} won!", p2.name()));
-						if let Some(url) = p2.icon() {
-							e.thumbnail(url)
-						} else {
-							e
-						}
+						e
 					}
-
-				}).components(|c| c)
+				}).components(|c| create_rematch_action_row(c, same_loadouts))
 			} else {
-				m.embed(|e| base_embed(e)
-					.title("The battle was a tie...")
-					.field("Log", &self.log, false)
-				).components(|c| c)
+				m.embed(|e| {
+					let e = base_embed(e)
+						.title("The battle was a tie...")
+						.field("Log", log, false)
+						.footer(|f| f.text(&footer));
+
+					if let Some(decision) = &decision {
+						e.description(decision)
+					} else {
+						e
+					}
+				}).components(|c| create_rematch_action_row(c, same_loadouts))
 			}
 		).await?;
 
@@ -174,8 +944,45 @@ impl<'a> Battle<'a> {
 	}
 }
 
-impl Drop for Battle<'_> {
-	fn drop(&mut self) {
-		self.ctx.data().battles.write().unwrap().remove(&self.id);
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use poise::serenity_prelude::ReactionType;
+
+	fn npc_with_potions(quantity: usize) -> Box<dyn Battler> {
+		let potion = Item {
+			name: "Potion".into(),
+			id: 1,
+			description: "Heals a little.".into(),
+			icon: ReactionType::Unicode("🧪".into()),
+			lua: String::new(),
+			area: false,
+			quantity,
+			rarity: Rarity::Common,
+		};
+		let loadout = Loadout {
+			weapon: Weapon::default(),
+			secondary: None,
+			items: HashMap::from([(potion.id, potion)]),
+			skills: vec![],
+		};
+		Box::new(NpcBattler::with_loadout("NPC", None, Box::new(Balanced::default()), loadout))
+	}
+
+	#[test]
+	fn taking_an_item_takes_one_from_its_stack() {
+		let mut battler = npc_with_potions(2);
+		let item = battler.take_item(1).unwrap();
+		assert_eq!(item.quantity, 1);
+		assert_eq!(battler.items()[&1].quantity, 1);
+	}
+
+	#[test]
+	fn taking_the_last_item_removes_its_stack() {
+		let mut battler = npc_with_potions(1);
+		assert!(battler.take_item(1).is_some());
+		assert!(battler.items().is_empty());
+		assert!(battler.take_item(1).is_none());
 	}
 }
@@ -0,0 +1,75 @@
+use crate::prelude::*;
+use super::{
+	Battler,
+	ai::{Balanced, Strategy},
+	armory::{Armory, Loadout},
+	snapshot::BattlerRef,
+	state::BattlerState,
+};
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Strategies aren't saved, so restored NPCs fall back to the default one.
+fn default_strategy() -> Box<dyn Strategy> {
+	Box::new(Balanced::default())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NpcBattler {
+	id: Uuid,
+	name: String,
+	icon: Option<String>,
+	#[serde(skip, default = "default_strategy")]
+	strategy: Box<dyn Strategy>,
+	#[serde(flatten)]
+	state: BattlerState,
+}
+
+impl NpcBattler {
+	pub fn new<T: Into<String>>(name: T, icon: Option<String>, strategy: Box<dyn Strategy>, armory: &Armory, rng: &mut impl Rng) -> Result<Self, Error> {
+		Ok(Self::with_loadout(name, icon, strategy, armory.draw_loadout(rng)?))
+	}
+
+	pub fn with_loadout<T: Into<String>>(name: T, icon: Option<String>, strategy: Box<dyn Strategy>, loadout: Loadout) -> Self {
+		Self {
+			id: Uuid::new_v4(),
+			name: name.into(),
+			icon,
+			strategy,
+			state: BattlerState::new(loadout),
+		}
+	}
+}
+
+impl Battler for NpcBattler {
+	fn id(&self) -> &Uuid {
+		&self.id
+	}
+	fn name(&self) -> &String {
+		&self.name
+	}
+	fn icon(&self) -> Option<String> {
+		self.icon.clone()
+	}
+	fn strategy(&self) -> Option<&dyn Strategy> {
+		Some(self.strategy.as_ref())
+	}
+
+	fn state(&self) -> &BattlerState {
+		&self.state
+	}
+	fn state_mut(&mut self) -> &mut BattlerState {
+		&mut self.state
+	}
+
+	/// Strategies can't be copied, so the new NPC falls back to the default one.
+	fn rematch(&self, loadout: Loadout) -> Box<dyn Battler> {
+		Box::new(NpcBattler::with_loadout(self.name.clone(), self.icon.clone(), default_strategy(), loadout))
+	}
+
+	fn snapshot(&self) -> BattlerRef<'_> {
+		BattlerRef::Npc(self)
+	}
+}
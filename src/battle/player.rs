@@ -0,0 +1,72 @@
+use crate::prelude::*;
+use super::{
+	Battler,
+	armory::{Armory, Loadout},
+	snapshot::BattlerRef,
+	state::BattlerState,
+};
+
+use poise::serenity_prelude::{User, UserId};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+pub struct Player {
+	user: User,
+	id: Uuid,
+	#[serde(flatten)]
+	state: BattlerState,
+}
+
+impl Player {
+	pub fn new(user: User, armory: &Armory, rng: &mut impl Rng) -> Result<Self, Error> {
+		Ok(Self::with_loadout(user, armory.draw_loadout(rng)?))
+	}
+
+	pub fn with_loadout(user: User, loadout: Loadout) -> Self {
+		Self {
+			user,
+			id: Uuid::new_v4(),
+			state: BattlerState::new(loadout),
+		}
+	}
+
+	pub fn user(&self) -> &User {
+		&self.user
+	}
+
+	pub fn mention(&self) -> String {
+		format!("<@{}>", self.user.id)
+	}
+}
+
+impl Battler for Player {
+	fn user_id(&self) -> Option<UserId> {
+		Some(self.user.id)
+	}
+	fn id(&self) -> &Uuid {
+		&self.id
+	}
+	fn name(&self) -> &String {
+		&self.user.name
+	}
+	fn icon(&self) -> Option<String> {
+		self.user.avatar_url()
+	}
+
+	fn state(&self) -> &BattlerState {
+		&self.state
+	}
+	fn state_mut(&mut self) -> &mut BattlerState {
+		&mut self.state
+	}
+
+	fn rematch(&self, loadout: Loadout) -> Box<dyn Battler> {
+		Box::new(Player::with_loadout(self.user.clone(), loadout))
+	}
+
+	fn snapshot(&self) -> BattlerRef<'_> {
+		BattlerRef::Player(self)
+	}
+}
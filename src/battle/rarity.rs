@@ -0,0 +1,140 @@
+use std::{fmt::Display, str::FromStr};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Rarity {
+	#[default]
+	Common,
+	Rare,
+	Epic,
+	Legendary,
+}
+
+impl Rarity {
+	pub fn icon(&self) -> char {
+		match self {
+			Rarity::Common => '⚪',
+			Rarity::Rare => '🔵',
+			Rarity::Epic => '🟣',
+			Rarity::Legendary => '🟠',
+		}
+	}
+
+	/// The rarity's colored icon followed by its name.
+	pub fn label(&self) -> String {
+		let name = match self {
+			Rarity::Common => "Common",
+			Rarity::Rare => "Rare",
+			Rarity::Epic => "Epic",
+			Rarity::Legendary => "Legendary",
+		};
+
+		format!("{} {}", self.icon(), name)
+	}
+}
+
+impl Display for Rarity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Rarity::Common => "common",
+			Rarity::Rare => "rare",
+			Rarity::Epic => "epic",
+			Rarity::Legendary => "legendary",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for Rarity {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"common" => Ok(Rarity::Common),
+			"rare" => Ok(Rarity::Rare),
+			"epic" => Ok(Rarity::Epic),
+			"legendary" => Ok(Rarity::Legendary),
+			other => Err(format!("Unknown rarity {other}.")),
+		}
+	}
+}
+
+/// How likely each rarity is to be drawn, relative to the others.
+#[derive(Clone, Copy, Debug)]
+pub struct RarityWeights {
+	pub common: f64,
+	pub rare: f64,
+	pub epic: f64,
+	pub legendary: f64,
+}
+
+impl RarityWeights {
+	pub fn of(&self, rarity: Rarity) -> f64 {
+		match rarity {
+			Rarity::Common => self.common,
+			Rarity::Rare => self.rare,
+			Rarity::Epic => self.epic,
+			Rarity::Legendary => self.legendary,
+		}
+	}
+}
+
+impl Default for RarityWeights {
+	fn default() -> Self {
+		Self {
+			common: 60.0,
+			rare: 25.0,
+			epic: 10.0,
+			legendary: 5.0,
+		}
+	}
+}
+
+/// Parses weights for common, rare, epic and legendary, in that order, separated by commas.
+impl FromStr for RarityWeights {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let weights = s
+			.split(',')
+			.map(|weight| weight.trim().parse::<f64>().map_err(|e| format!("Invalid rarity weight {weight}: {e}.")))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		if weights.iter().any(|weight| !weight.is_finite() || *weight < 0.0) {
+			return Err("Rarity weights cannot be negative.".into());
+		}
+
+		match weights[..] {
+			[common, rare, epic, legendary] => Ok(Self { common, rare, epic, legendary }),
+			_ => Err("There must be a rarity weight for each of common, rare, epic and legendary.".into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn weights_are_parsed_in_order() {
+		let weights: RarityWeights = "4, 3,2 ,1".parse().unwrap();
+		assert_eq!(weights.of(Rarity::Common), 4.0);
+		assert_eq!(weights.of(Rarity::Legendary), 1.0);
+	}
+
+	#[test]
+	fn weights_need_one_for_each_rarity() {
+		assert!("1,2,3".parse::<RarityWeights>().is_err());
+		assert!("1,2,3,4,5".parse::<RarityWeights>().is_err());
+		assert!("".parse::<RarityWeights>().is_err());
+	}
+
+	#[test]
+	fn weights_must_be_non_negative_numbers() {
+		assert!("1,-2,3,4".parse::<RarityWeights>().is_err());
+		assert!("1,two,3,4".parse::<RarityWeights>().is_err());
+		assert!("1,inf,3,4".parse::<RarityWeights>().is_err());
+		assert!("1,NaN,3,4".parse::<RarityWeights>().is_err());
+	}
+}
@@ -0,0 +1,181 @@
+use crate::{
+	prelude::*,
+	model::{NewReplay, QueryReplay},
+	schema::replays,
+	util::establish_connection,
+};
+use super::{
+	Battler,
+	util::{create_replay_components, create_replay_embed},
+};
+
+use std::time::Duration;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A battler's health and armor at one point of a replay.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BattlerSnapshot {
+	pub name: String,
+	pub health: usize,
+	pub max_health: usize,
+	pub armor: usize,
+}
+
+impl BattlerSnapshot {
+	pub fn of(battlers: &[Box<dyn Battler>]) -> Vec<Self> {
+		battlers
+			.iter()
+			.map(|battler| Self {
+				name: battler.name().clone(),
+				health: battler.health(),
+				max_health: battler.max_health(),
+				armor: battler.armor(),
+			})
+			.collect()
+	}
+}
+
+/// Something that happened during a battle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Event {
+	Start { seed: u64, battlers: Vec<BattlerSnapshot> },
+	Turn { battler: String, action: String },
+	Roll { source: String, result: String },
+	Entry(String),
+	State(Vec<BattlerSnapshot>),
+	End { winner: Option<String> },
+}
+
+/// A single step of a replay, covering one turn.
+pub struct Frame {
+	pub title: String,
+	pub lines: Vec<String>,
+	pub battlers: Vec<BattlerSnapshot>,
+}
+
+pub struct Replay {
+	pub id: Uuid,
+	pub seed: u64,
+	pub events: Vec<Event>,
+}
+
+impl Replay {
+	pub fn load(id: &Uuid) -> Result<Option<Self>, Error> {
+		let conn = establish_connection()?;
+		let replay = replays::table
+			.find(id.to_string())
+			.first::<QueryReplay>(&conn)
+			.optional()?;
+
+		if let Some(replay) = replay {
+			Ok(Some(Self {
+				id: Uuid::parse_str(&replay.id)?,
+				seed: replay.seed as u64,
+				events: serde_json::from_str(&replay.events)?,
+			}))
+		} else {
+			Ok(None)
+		}
+	}
+
+	pub fn save(&self) -> Result<(), Error> {
+		let conn = establish_connection()?;
+		let events = serde_json::to_string(&self.events)?;
+
+		diesel::insert_into(replays::table)
+			.values(&NewReplay {
+				id: &self.id.to_string(),
+				seed: self.seed as i64,
+				events: &events,
+			})
+			.execute(&conn)?;
+
+		Ok(())
+	}
+
+	pub fn frames(&self) -> Vec<Frame> {
+		let mut frames: Vec<Frame> = vec![];
+		let mut battlers = vec![];
+
+		for event in &self.events {
+			match event {
+				Event::Start { seed, battlers: start } => {
+					battlers = start.clone();
+					frames.push(Frame {
+						title: "Start".into(),
+						lines: vec![format!("🎲 Seed {seed}")],
+						battlers: battlers.clone(),
+					});
+				},
+				Event::Turn { battler, action } => frames.push(Frame {
+					title: format!("{battler}: {action}"),
+					lines: vec![],
+					battlers: battlers.clone(),
+				}),
+				Event::Roll { source, result } => if let Some(frame) = frames.last_mut() {
+					frame.lines.push(format!("🎲 {source}: {result}"));
+				},
+				Event::Entry(entry) => if let Some(frame) = frames.last_mut() {
+					frame.lines.push(entry.clone());
+				},
+				Event::State(state) => {
+					battlers = state.clone();
+					if let Some(frame) = frames.last_mut() {
+						frame.battlers = battlers.clone();
+					}
+				},
+				Event::End { winner } => frames.push(Frame {
+					title: winner.as_ref().map_or_else(|| "The battle was a tie...".into(), |winner| format!("🏆 {winner} won!")),
+					lines: vec![],
+					battlers: battlers.clone(),
+				}),
+			}
+		}
+
+		frames
+	}
+
+	/// Steps through the replay in an embed with previous and next buttons.
+	pub async fn show(&self, ctx: Context<'_>) -> Result<(), Error> {
+		let frames = self.frames();
+		if frames.is_empty() {
+			return Err("The replay has no events.".into());
+		}
+
+		let mut index = 0;
+		let reply = ctx.send(|m|
+			m.embed(|e| create_replay_embed(e, &frames[index], index, frames.len(), self.seed))
+				.components(|c| create_replay_components(c, index, frames.len()))
+		).await?;
+
+		loop {
+			let interaction = reply
+				.message()
+				.await?
+				.await_component_interaction(ctx.discord())
+				.author_id(ctx.author().id)
+				.timeout(Duration::from_secs(300))
+				.await;
+
+			if let Some(m) = interaction {
+				m.defer(ctx.discord()).await?;
+
+				match &*m.data.custom_id {
+					"previous" => index = index.saturating_sub(1),
+					"next" => index = (index + 1).min(frames.len() - 1),
+					other => return Err(format!("Unknown ID {other}.").into()),
+				}
+
+				reply.edit(ctx, |m|
+					m.embed(|e| create_replay_embed(e, &frames[index], index, frames.len(), self.seed))
+						.components(|c| create_replay_components(c, index, frames.len()))
+				).await?;
+			} else {
+				reply.edit(ctx, |m| m.components(|c| c)).await?;
+				return Ok(());
+			}
+		}
+	}
+}
@@ -0,0 +1,97 @@
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+/// The random number generator of a single battle.
+///
+/// Everything random in a battle draws from this, so a battle can be reproduced from its seed.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "RngState", into = "RngState")]
+pub struct BattleRng {
+	seed: u64,
+	rng: ChaCha12Rng,
+}
+
+/// Enough to pick a battle's RNG back up where it left off.
+#[derive(Serialize, Deserialize)]
+struct RngState {
+	seed: u64,
+	word_pos: u64,
+}
+
+impl BattleRng {
+	pub fn new() -> Self {
+		Self::with_seed(rand::random())
+	}
+
+	pub fn with_seed(seed: u64) -> Self {
+		Self {
+			seed,
+			rng: ChaCha12Rng::seed_from_u64(seed),
+		}
+	}
+
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+}
+
+impl Default for BattleRng {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl From<RngState> for BattleRng {
+	fn from(state: RngState) -> Self {
+		let mut rng = Self::with_seed(state.seed);
+		rng.rng.set_word_pos(state.word_pos.into());
+		rng
+	}
+}
+
+impl From<BattleRng> for RngState {
+	fn from(rng: BattleRng) -> Self {
+		Self {
+			seed: rng.seed,
+			word_pos: rng.rng.get_word_pos() as u64,
+		}
+	}
+}
+
+impl RngCore for BattleRng {
+	fn next_u32(&mut self) -> u32 {
+		self.rng.next_u32()
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		self.rng.next_u64()
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		self.rng.fill_bytes(dest)
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+		self.rng.try_fill_bytes(dest)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn saved_rng_resumes_the_same_stream() {
+		let mut rng = BattleRng::with_seed(42);
+		for _ in 0..5 {
+			rng.next_u32();
+		}
+
+		let mut restored: BattleRng = serde_json::from_str(&serde_json::to_string(&rng).unwrap()).unwrap();
+		assert_eq!(restored.seed(), 42);
+		for _ in 0..10 {
+			assert_eq!(restored.next_u64(), rng.next_u64());
+		}
+	}
+}
@@ -0,0 +1,220 @@
+use crate::{
+	prelude::*,
+	battle::{
+		Battler,
+		damage::DamageType,
+		log::{Entry, Log},
+		replay::Event,
+		status::{Status, StatusKind},
+	},
+};
+
+use std::{cell::RefCell, collections::HashMap};
+use poise::serenity_prelude::ReactionType;
+use rand::prelude::*;
+use rlua::{Lua, Table, Value};
+
+fn parse_damage_type(damage_type: Option<String>) -> Result<DamageType, rlua::Error> {
+	damage_type.map_or(Ok(DamageType::Physical), |damage_type| damage_type.parse().map_err(rlua::Error::RuntimeError))
+}
+
+/// Runs `f` on the user if `target` is "user", or on every opponent if it is "opponent".
+fn for_target(target: &str, user: &RefCell<&mut dyn Battler>, targets: &RefCell<Vec<&mut dyn Battler>>, f: impl Fn(&mut dyn Battler)) -> Result<(), rlua::Error> {
+	match target {
+		"user" => f(&mut **user.borrow_mut()),
+		"opponent" => targets.borrow_mut().iter_mut().for_each(|target| f(&mut **target)),
+		other => return Err(rlua::Error::RuntimeError(format!("Unknown target {other}."))),
+	}
+	Ok(())
+}
+
+/// A Lua script with the battle API, along with the hooks to call after it has run.
+pub struct Script<'a> {
+	name: &'a str,
+	icon: &'a ReactionType,
+	source: &'a str,
+	hooks: Vec<(&'static str, usize)>,
+	memory: Option<&'a mut HashMap<String, f64>>,
+}
+
+impl<'a> Script<'a> {
+	pub fn new(name: &'a str, icon: &'a ReactionType, source: &'a str) -> Self {
+		Self {
+			name,
+			icon,
+			source,
+			hooks: vec![],
+			memory: None,
+		}
+	}
+
+	/// Calls the global function `hook` with `damage` after the script has run, if the script defines it.
+	pub fn hook(mut self, hook: &'static str, damage: usize) -> Self {
+		self.hooks.push((hook, damage));
+		self
+	}
+
+	/// Lets the script keep numbers between runs with `remember` and `recall`.
+	pub fn memory(mut self, memory: &'a mut HashMap<String, f64>) -> Self {
+		self.memory = Some(memory);
+		self
+	}
+
+	/// Runs the script. The `opponent` functions affect every target and return the total.
+	pub fn run(self, user: &mut dyn Battler, targets: Vec<&mut dyn Battler>, log: &mut Log, rng: &mut impl Rng) -> Result<(), Error> {
+		let Script { name, icon, source, hooks, memory } = self;
+		let opponent_name = targets.iter().map(|target| target.name().as_str()).collect::<Vec<_>>().join(" and ");
+		let user = RefCell::new(user);
+		let targets = RefCell::new(targets);
+		let log = RefCell::new(log);
+		let memory = RefCell::new(memory);
+
+		let lua = Lua::new();
+		lua.context(|lua_ctx| -> Result<(), Error> {
+			lua_ctx.scope(|scope| -> Result<(), Error> {
+				let globals = lua_ctx.globals();
+
+				globals.set("user_name", user.borrow().name().clone())?;
+				globals.set("opponent_name", opponent_name)?;
+
+				let add_log_entry = scope.create_function_mut(|_, entry: String| {
+					log.borrow_mut().add(Entry::Item(icon.clone(), entry));
+					Ok(())
+				})?;
+				globals.set("add_log_entry", add_log_entry)?;
+
+				// Behaves like `math.random`, but draws from the battle's RNG.
+				let random = scope.create_function_mut(|_, (min, max): (Option<i64>, Option<i64>)| {
+					let (min, max) = match (min, max) {
+						(None, _) => {
+							let result: f64 = rng.gen();
+							log.borrow_mut().record(Event::Roll {
+								source: format!("{} random()", name),
+								result: result.to_string(),
+							});
+							return Ok(Value::Number(result));
+						},
+						(Some(max), None) => (1, max),
+						(Some(min), Some(max)) => (min, max),
+					};
+
+					if min > max {
+						return Err(rlua::Error::RuntimeError("bad argument to 'random' (interval is empty)".into()));
+					}
+
+					let result = rng.gen_range(min..=max);
+					log.borrow_mut().record(Event::Roll {
+						source: format!("{} random({min}, {max})", name),
+						result: result.to_string(),
+					});
+					Ok(Value::Integer(result))
+				})?;
+				let math: Table = globals.get("math")?;
+				math.set("random", random.clone())?;
+				globals.set("random", random)?;
+
+				let heal_user = scope.create_function_mut(|_, healing: usize| {
+					Ok(user.borrow_mut().heal(healing))
+				})?;
+				globals.set("heal_user", heal_user)?;
+
+				let heal_opponent = scope.create_function_mut(|_, healing: usize| {
+					Ok(targets.borrow_mut().iter_mut().map(|target| target.heal(healing)).sum::<usize>())
+				})?;
+				globals.set("heal_opponent", heal_opponent)?;
+
+				// The damage type is optional and defaults to physical.
+				let damage_user = scope.create_function_mut(|_, (damage, pierce, damage_type): (usize, usize, Option<String>)| {
+					let damage_type = parse_damage_type(damage_type)?;
+					Ok(user.borrow_mut().damage(damage, pierce, damage_type))
+				})?;
+				globals.set("damage_user", damage_user)?;
+
+				let damage_opponent = scope.create_function_mut(|_, (damage, pierce, damage_type): (usize, usize, Option<String>)| {
+					let damage_type = parse_damage_type(damage_type)?;
+					let mut user = user.borrow_mut();
+					let mut log = log.borrow_mut();
+					Ok(targets.borrow_mut().iter_mut().map(|target| user.strike(&mut **target, damage, pierce, damage_type, &mut log)).sum::<usize>())
+				})?;
+				globals.set("damage_opponent", damage_opponent)?;
+
+				let add_armor = scope.create_function_mut(|_, armor: usize| {
+					user.borrow_mut().add_armor(armor);
+					Ok(())
+				})?;
+				globals.set("add_armor", add_armor)?;
+
+				// The functions below take a target of "user" or "opponent". Negative amounts lower a stat instead of raising it.
+				let add_speed = scope.create_function_mut(|_, (target, speed): (String, isize)| {
+					for_target(&target, &user, &targets, |battler| battler.add_speed(speed))
+				})?;
+				globals.set("add_speed", add_speed)?;
+
+				let add_evasion = scope.create_function_mut(|_, (target, evasion): (String, isize)| {
+					for_target(&target, &user, &targets, |battler| battler.add_evasion(evasion))
+				})?;
+				globals.set("add_evasion", add_evasion)?;
+
+				let add_lifesteal = scope.create_function_mut(|_, (target, lifesteal): (String, isize)| {
+					for_target(&target, &user, &targets, |battler| battler.add_lifesteal(lifesteal))
+				})?;
+				globals.set("add_lifesteal", add_lifesteal)?;
+
+				let add_thorns = scope.create_function_mut(|_, (target, thorns): (String, isize)| {
+					for_target(&target, &user, &targets, |battler| battler.add_thorns(thorns))
+				})?;
+				globals.set("add_thorns", add_thorns)?;
+
+				let add_energy = scope.create_function_mut(|_, (target, energy): (String, isize)| {
+					for_target(&target, &user, &targets, |battler| battler.add_energy(energy))
+				})?;
+				globals.set("add_energy", add_energy)?;
+
+				let apply_status = scope.create_function_mut(|_, (target, kind, turns, potency): (String, String, usize, Option<usize>)| {
+					let kind: StatusKind = kind.parse().map_err(rlua::Error::RuntimeError)?;
+					let status = Status::new(kind, turns, potency.unwrap_or(0));
+					for_target(&target, &user, &targets, |battler| battler.apply_status(status.clone()))
+				})?;
+				globals.set("apply_status", apply_status)?;
+
+				// Negative resistances are weaknesses.
+				let add_resistance = scope.create_function_mut(|_, (target, damage_type, resistance): (String, String, isize)| {
+					let damage_type = parse_damage_type(Some(damage_type))?;
+					for_target(&target, &user, &targets, |battler| battler.add_resistance(damage_type, resistance))
+				})?;
+				globals.set("add_resistance", add_resistance)?;
+
+				// Unknown keys, and every key for scripts without memory, recall as 0.
+				let remember = scope.create_function_mut(|_, (key, value): (String, f64)| {
+					if let Some(memory) = memory.borrow_mut().as_mut() {
+						memory.insert(key, value);
+					}
+					Ok(())
+				})?;
+				globals.set("remember", remember)?;
+
+				let recall = scope.create_function(|_, key: String| {
+					Ok(memory.borrow().as_ref().and_then(|memory| memory.get(&key).copied()).unwrap_or(0.0))
+				})?;
+				globals.set("recall", recall)?;
+
+				lua_ctx
+					.load(source)
+					.set_name(name)?
+					.exec()?;
+
+				for (hook, damage) in hooks {
+					if let Value::Function(hook) = globals.get::<_, Value>(hook)? {
+						hook.call::<_, ()>(damage)?;
+					}
+				}
+
+				Ok(())
+			})?;
+
+			Ok(())
+		})?;
+
+		Ok(())
+	}
+}
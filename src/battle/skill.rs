@@ -0,0 +1,59 @@
+use crate::{
+	prelude::*,
+	battle::{
+		Battler,
+		log::Log,
+		script::Script,
+		util::option_description,
+	},
+	model::QuerySkill,
+};
+
+use poise::serenity_prelude::{CreateSelectMenuOption, ReactionType};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A reusable action that costs energy and can't be used again for `cooldown` of its user's turns.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Skill {
+	pub name: String,
+	pub id: Uuid,
+	pub description: String,
+	pub icon: ReactionType,
+	pub lua: String,
+	pub cost: usize,
+	pub cooldown: usize,
+	pub area: bool,
+}
+
+impl Skill {
+	/// Runs the skill's script.
+	pub fn cast(&self, user: &mut dyn Battler, targets: Vec<&mut dyn Battler>, log: &mut Log, rng: &mut impl Rng) -> Result<(), Error> {
+		Script::new(&self.name, &self.icon, &self.lua).run(user, targets, log, rng)
+	}
+
+	pub fn as_option<'a>(&self, o: &'a mut CreateSelectMenuOption) -> &'a mut CreateSelectMenuOption {
+		o.label(&self.name)
+			.value(self.id)
+			.description(option_description(format!("⚡ {} • {}", self.cost, self.description)))
+			.emoji(self.icon.clone())
+	}
+}
+
+impl TryFrom<QuerySkill> for Skill {
+	type Error = Error;
+
+	fn try_from(skill: QuerySkill) -> Result<Self, Self::Error> {
+		Ok(Self {
+			name: skill.name,
+			id: Uuid::new_v4(),
+			description: skill.description,
+			icon: skill.icon.try_into()?,
+			lua: skill.lua,
+			cost: skill.cost.max(0).try_into()?,
+			cooldown: skill.cooldown.max(0).try_into()?,
+			area: skill.area,
+		})
+	}
+}
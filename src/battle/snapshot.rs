@@ -0,0 +1,115 @@
+use crate::{
+	prelude::*,
+	model::{NewBattle, QueryBattle},
+	schema::battles,
+	util::establish_connection,
+};
+use super::{
+	Battler,
+	engine::{BattleEngine, EngineSnapshot},
+	npc::NpcBattler,
+	player::Player,
+};
+
+use diesel::prelude::*;
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A battler borrowed to be saved.
+#[derive(Serialize)]
+pub enum BattlerRef<'a> {
+	Player(&'a Player),
+	Npc(&'a NpcBattler),
+}
+
+/// A battler restored from a save, with the same variants as [`BattlerRef`].
+#[derive(Deserialize)]
+pub enum StoredBattler {
+	Player(Player),
+	Npc(NpcBattler),
+}
+
+impl StoredBattler {
+	pub fn into_battler(self) -> Box<dyn Battler> {
+		match self {
+			StoredBattler::Player(player) => Box::new(player),
+			StoredBattler::Npc(npc) => Box::new(npc),
+		}
+	}
+}
+
+/// An unfinished battle and the message it is shown in, as stored in the database.
+pub struct SavedBattle {
+	pub guild_id: GuildId,
+	pub channel_id: ChannelId,
+	pub message_id: MessageId,
+	pub engine: EngineSnapshot<StoredBattler>,
+}
+
+/// The message of a saved battle that couldn't be read.
+pub struct UnreadableBattle {
+	pub channel_id: ChannelId,
+	pub message_id: MessageId,
+}
+
+impl SavedBattle {
+	/// Stores the battle, replacing any earlier save of it.
+	pub fn save(engine: &BattleEngine, guild_id: GuildId, channel_id: ChannelId, message_id: MessageId) -> Result<(), Error> {
+		let conn = establish_connection()?;
+		let id = engine.id().to_string();
+		let state = serde_json::to_string(&engine.snapshot())?;
+
+		diesel::insert_into(battles::table)
+			.values(&NewBattle {
+				id: &id,
+				guild_id: guild_id.0 as i64,
+				channel_id: channel_id.0 as i64,
+				message_id: message_id.0 as i64,
+				state: &state,
+			})
+			.on_conflict(battles::id)
+			.do_update()
+			.set(battles::state.eq(&state))
+			.execute(&conn)?;
+
+		Ok(())
+	}
+
+	/// Loads every saved battle.
+	///
+	/// Battles that can't be read, such as those saved by an older version, are deleted and returned separately,
+	/// so the buttons on their messages can be taken away.
+	pub fn load_all() -> Result<(Vec<Self>, Vec<UnreadableBattle>), Error> {
+		let conn = establish_connection()?;
+		let battles = battles::table.load::<QueryBattle>(&conn)?;
+
+		let mut saved = vec![];
+		let mut unreadable = vec![];
+		for battle in battles {
+			let channel_id = ChannelId(battle.channel_id as u64);
+			let message_id = MessageId(battle.message_id as u64);
+			match serde_json::from_str(&battle.state) {
+				Ok(engine) => saved.push(Self {
+					guild_id: GuildId(battle.guild_id as u64),
+					channel_id,
+					message_id,
+					engine,
+				}),
+				Err(e) => {
+					eprintln!("Could not read saved battle {}: {:?}", battle.id, e);
+					diesel::delete(battles::table.find(battle.id.as_str())).execute(&conn)?;
+					unreadable.push(UnreadableBattle { channel_id, message_id });
+				},
+			}
+		}
+
+		Ok((saved, unreadable))
+	}
+
+	pub fn delete(id: &Uuid) -> Result<(), Error> {
+		let conn = establish_connection()?;
+		diesel::delete(battles::table.find(id.to_string())).execute(&conn)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,63 @@
+use super::{
+	armory::Loadout,
+	damage::DamageType,
+	item::{self, Item},
+	skill::Skill,
+	status::Status,
+	weapon::Weapon,
+};
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The stats and equipment every kind of battler has, which battlers expose through [`Battler::state`](super::Battler::state).
+#[derive(Serialize, Deserialize)]
+pub struct BattlerState {
+	pub health: usize,
+	pub max_health: usize,
+	pub weapon: Weapon,
+	#[serde(default)]
+	pub secondary: Option<Weapon>,
+	#[serde(deserialize_with = "item::deserialize_items")]
+	pub items: HashMap<i32, Item>,
+	pub skills: Vec<Skill>,
+	pub cooldowns: HashMap<Uuid, usize>,
+	pub armor: usize,
+	pub speed: usize,
+	#[serde(default)]
+	pub evasion: usize,
+	#[serde(default)]
+	pub lifesteal: usize,
+	#[serde(default)]
+	pub thorns: usize,
+	pub energy: usize,
+	pub max_energy: usize,
+	pub statuses: Vec<Status>,
+	#[serde(default)]
+	pub resistances: HashMap<DamageType, isize>,
+}
+
+impl BattlerState {
+	/// A battler at full health, carrying the given loadout.
+	pub fn new(loadout: Loadout) -> Self {
+		Self {
+			health: 100,
+			max_health: 100,
+			weapon: loadout.weapon,
+			secondary: loadout.secondary,
+			items: loadout.items,
+			skills: loadout.skills,
+			cooldowns: HashMap::new(),
+			armor: 0,
+			speed: 100,
+			evasion: 5,
+			lifesteal: 0,
+			thorns: 0,
+			energy: 50,
+			max_energy: 100,
+			statuses: vec![],
+			resistances: HashMap::new(),
+		}
+	}
+}
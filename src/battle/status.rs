@@ -0,0 +1,191 @@
+use super::{
+	Battler,
+	damage::DamageType,
+	log::{Entry, Log},
+};
+
+use std::{fmt::Display, str::FromStr};
+use serde::{Deserialize, Serialize};
+
+/// How many times a status can stack on a single battler.
+pub const MAX_STACKS: usize = 5;
+/// The duration of statuses that last until the end of the battle.
+pub const PERMANENT: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusKind {
+	Poison,
+	Burn,
+	Stun,
+	Regeneration,
+	Weakness,
+	Guard,
+	Wounded,
+	Evasion,
+	Focus,
+}
+
+/// When a status ticks during its battler's turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickPhase {
+	Start,
+	End,
+}
+
+impl StatusKind {
+	pub fn icon(&self) -> char {
+		match self {
+			StatusKind::Poison => '🤢',
+			StatusKind::Burn => '🔥',
+			StatusKind::Stun => '💫',
+			StatusKind::Regeneration => '💚',
+			StatusKind::Weakness => '🥀',
+			StatusKind::Guard => '🛡',
+			StatusKind::Wounded => '🩸',
+			StatusKind::Evasion => '🌫',
+			StatusKind::Focus => '🎯',
+		}
+	}
+
+	pub fn phase(&self) -> TickPhase {
+		match self {
+			StatusKind::Poison | StatusKind::Stun | StatusKind::Regeneration | StatusKind::Guard | StatusKind::Evasion => TickPhase::Start,
+			StatusKind::Burn | StatusKind::Weakness | StatusKind::Wounded | StatusKind::Focus => TickPhase::End,
+		}
+	}
+}
+
+impl Display for StatusKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			StatusKind::Poison => "poison",
+			StatusKind::Burn => "burn",
+			StatusKind::Stun => "stun",
+			StatusKind::Regeneration => "regeneration",
+			StatusKind::Weakness => "weakness",
+			StatusKind::Guard => "guard",
+			StatusKind::Wounded => "wounded",
+			StatusKind::Evasion => "evasion",
+			StatusKind::Focus => "focus",
+		};
+
+		write!(f, "{}", name)
+	}
+}
+
+impl FromStr for StatusKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"poison" => Ok(StatusKind::Poison),
+			"burn" => Ok(StatusKind::Burn),
+			"stun" => Ok(StatusKind::Stun),
+			"regeneration" => Ok(StatusKind::Regeneration),
+			"weakness" => Ok(StatusKind::Weakness),
+			"guard" => Ok(StatusKind::Guard),
+			"wounded" => Ok(StatusKind::Wounded),
+			"evasion" => Ok(StatusKind::Evasion),
+			"focus" => Ok(StatusKind::Focus),
+			other => Err(format!("Unknown status {other}.")),
+		}
+	}
+}
+
+/// A lasting effect on a battler.
+///
+/// Poison and burn deal `potency` damage per stack through armor, regeneration heals `potency` per stack,
+/// weakness cuts attack damage by `potency` percent per stack, guard cuts incoming damage by `potency` percent,
+/// wounded blocks all healing, stun skips the battler's turns, evasion adds `potency` percent per stack to the chance
+/// of dodging attacks, and focus makes the battler's next attack unable to miss.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Status {
+	pub kind: StatusKind,
+	pub turns: usize,
+	pub stacks: usize,
+	pub potency: usize,
+	/// Armor the status grants, which is taken away again when it ends.
+	pub armor: usize,
+}
+
+impl Status {
+	pub fn new(kind: StatusKind, turns: usize, potency: usize) -> Self {
+		Self {
+			kind,
+			turns,
+			stacks: 1,
+			potency,
+			armor: 0,
+		}
+	}
+
+	pub fn with_armor(mut self, armor: usize) -> Self {
+		self.armor = armor;
+		self
+	}
+}
+
+impl Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match (self.stacks > 1, self.turns == PERMANENT) {
+			(true, true) => write!(f, "{}×{}", self.kind.icon(), self.stacks),
+			(true, false) => write!(f, "{}×{} {}t", self.kind.icon(), self.stacks, self.turns),
+			(false, true) => write!(f, "{}", self.kind.icon()),
+			(false, false) => write!(f, "{} {}t", self.kind.icon(), self.turns),
+		}
+	}
+}
+
+/// Ticks every status of a battler for the given phase of its turn.
+///
+/// Returns whether the battler is stunned.
+pub fn tick_statuses(battler: &mut dyn Battler, phase: TickPhase, log: &mut Log) -> bool {
+	if battler.health() == 0 {
+		return false;
+	}
+
+	let mut stunned = false;
+
+	// The statuses stay on the battler while they tick, so guard and wounded still apply to the damage and healing they cause.
+	for i in 0..battler.statuses().len() {
+		let status = battler.statuses()[i].clone();
+		if status.kind.phase() != phase {
+			continue;
+		}
+
+		let amount = status.potency.saturating_mul(status.stacks);
+		match status.kind {
+			StatusKind::Poison | StatusKind::Burn => {
+				let damage_type = if status.kind == StatusKind::Burn { DamageType::Fire } else { DamageType::Poison };
+				let damage = battler.damage(amount, usize::MAX, damage_type);
+				log.add(Entry::StatusDamage(status.kind, battler.name().clone(), damage));
+			},
+			StatusKind::Regeneration => {
+				let healing = battler.heal(amount);
+				log.add(Entry::StatusHeal(status.kind, battler.name().clone(), healing));
+			},
+			StatusKind::Stun => {
+				stunned = true;
+				log.add(Entry::Stunned(battler.name().clone()));
+			},
+			StatusKind::Weakness | StatusKind::Guard | StatusKind::Wounded | StatusKind::Evasion | StatusKind::Focus => {},
+		}
+
+		if status.turns == PERMANENT {
+			continue;
+		}
+
+		let turns = status.turns.saturating_sub(1);
+		battler.statuses_mut()[i].turns = turns;
+		// Guard only lasts until the battler's next turn, so it isn't worth a log entry.
+		if turns == 0 && status.kind != StatusKind::Guard {
+			log.add(Entry::StatusExpired(status.kind, battler.name().clone()));
+		}
+	}
+
+	let armor: usize = battler.statuses().iter().filter(|status| status.turns == 0).map(|status| status.armor).sum();
+	battler.statuses_mut().retain(|status| status.turns > 0);
+	battler.set_armor(battler.armor().saturating_sub(armor));
+
+	stunned
+}
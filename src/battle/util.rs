@@ -1,18 +1,25 @@
-use crate::prelude::*;
+use crate::{prelude::*, util::base_embed};
+use super::{
+	damage::DamageType,
+	log::Log,
+	rarity::Rarity,
+	replay::Frame,
+	status::Status,
+};
 
-use poise::serenity_prelude::{ButtonStyle, CreateComponents, Emoji, Guild, ReactionType, read_image};
+use poise::serenity_prelude::{self as serenity, ButtonStyle, CreateComponents, CreateEmbed, Emoji, Guild, GuildId, ReactionType, read_image};
 
-async fn get_or_create_emoji(emojis: &[Emoji], name: &str, guild: &Guild, ctx: Context<'_>) -> Result<Emoji, Error> {
+async fn get_or_create_emoji(emojis: &[Emoji], name: &str, guild: &Guild, discord: &serenity::Context) -> Result<Emoji, Error> {
 	if let Some(emoji) = emojis.iter().find(|emoji| emoji.name == name) {
 		Ok(emoji.clone())
 	} else {
-		Ok(guild.create_emoji(ctx.discord(), name, &read_image(format!("./img/{}.png", name))?).await?)
+		Ok(guild.create_emoji(discord, name, &read_image(format!("./img/{}.png", name))?).await?)
 	}
 }
 
-async fn create_health_bar(ctx: Context<'_>, health: usize, max_health: usize) -> Result<Vec<Emoji>, Error> {
-	let guild = ctx.guild().ok_or("No guild found.")?;
-	let emojis = guild.emojis(ctx.discord()).await?;
+async fn create_health_bar(discord: &serenity::Context, guild_id: GuildId, health: usize, max_health: usize) -> Result<Vec<Emoji>, Error> {
+	let guild = guild_id.to_guild_cached(discord).ok_or("No guild found.")?;
+	let emojis = guild.emojis(discord).await?;
 
 	const HEALTHBAR_LENGTH: usize = 6;
 	let percent_health_remaining = health as f64 / max_health as f64;
@@ -35,7 +42,7 @@ async fn create_health_bar(ctx: Context<'_>, health: usize, max_health: usize) -
 			format!("{fill_type}_middle")
 		};
 
-		healthbar.push(get_or_create_emoji(&emojis, &format!("bar_{bar_type}"), &guild, ctx).await?);
+		healthbar.push(get_or_create_emoji(&emojis, &format!("bar_{bar_type}"), &guild, discord).await?);
 	}
 
 	if healthbar.iter().any(|emoji| !emoji.available) { return Err("Some emojis aren't available.".into()) }
@@ -43,31 +50,79 @@ async fn create_health_bar(ctx: Context<'_>, health: usize, max_health: usize) -
 	Ok(healthbar)
 }
 
-pub struct BattlerInfo<'a> {
-	pub ctx: Context<'a>,
+fn create_energy_bar(energy: usize, max_energy: usize) -> String {
+	const ENERGYBAR_LENGTH: usize = 6;
+	let full = (ENERGYBAR_LENGTH * energy).checked_div(max_energy).unwrap_or(0).min(ENERGYBAR_LENGTH);
+
+	"🟦".repeat(full) + &"⬛".repeat(ENERGYBAR_LENGTH - full)
+}
+
+pub struct BattlerInfo {
 	pub name: String,
 	pub icon: Option<String>,
 	pub health: usize,
 	pub max_health: usize,
+	pub energy: usize,
+	pub max_energy: usize,
 	pub weapon: (ReactionType, String),
+	pub weapon_rarity: Rarity,
+	/// The weapon's durability out of its maximum, if it can break.
+	pub durability: Option<(usize, usize)>,
+	/// The weapon that can be switched to.
+	pub secondary: Option<(ReactionType, String)>,
 	pub armor: usize,
+	pub speed: usize,
+	pub evasion: usize,
+	pub lifesteal: usize,
+	pub thorns: usize,
+	pub statuses: Vec<Status>,
+	pub resistances: Vec<(DamageType, isize)>,
 }
 
-impl BattlerInfo<'_> {
-	pub async fn display(&self) -> BattlerDisplay {
-		let health = if let Ok(healthbar) = create_health_bar(self.ctx, self.health, self.max_health).await {
+impl BattlerInfo {
+	pub async fn display(&self, discord: &serenity::Context, guild_id: GuildId) -> BattlerDisplay {
+		let health = if let Ok(healthbar) = create_health_bar(discord, guild_id, self.health, self.max_health).await {
 			let healthbar = healthbar.iter().fold(String::new(), |acc, emoji| acc + &emoji.to_string());
 			format!("❤ {healthbar} {}", self.health)
 		} else {
 			format!("❤️ {}/{}", self.health, self.max_health)
 		};
 
-		let weapon = format!("{} {}", self.weapon.0, self.weapon.1);
+		let energy = format!("⚡ {} {}", create_energy_bar(self.energy, self.max_energy), self.energy);
+
+		let weapon = if let Some((durability, max_durability)) = self.durability {
+			format!("{} **{}** {} 🔧 {durability}/{max_durability}", self.weapon.0, self.weapon.1, self.weapon_rarity.label())
+		} else {
+			format!("{} **{}** {}", self.weapon.0, self.weapon.1, self.weapon_rarity.label())
+		};
+		let weapon = if let Some((icon, name)) = &self.secondary {
+			format!("{weapon}  🔄 {icon} {name}")
+		} else {
+			weapon
+		};
+
+		let mut armor = format!("🛡 {}  💨 {}  👟 {}%", self.armor, self.speed, self.evasion);
+		if self.lifesteal > 0 {
+			armor = format!("{armor}  🧛 {}%", self.lifesteal);
+		}
+		if self.thorns > 0 {
+			armor = format!("{armor}  🌵 {}%", self.thorns);
+		}
 
-		let armor = format!("🛡 {}", self.armor);
 
+		let mut stats = format!("{health}\n{energy}\n{weapon}\n{armor}");
+		if !self.statuses.is_empty() {
+			let statuses = self.statuses.iter().map(Status::to_string).collect::<Vec<_>>().join("  ");
+			stats = format!("{stats}\n{statuses}");
+		}
 
-		let stats = format!("{health}\n{weapon}\n{armor}");
+		// Shown as the change in damage taken, so resistances are negative.
+		let mut resistances: Vec<_> = self.resistances.iter().filter(|(_, resistance)| *resistance != 0).collect();
+		if !resistances.is_empty() {
+			resistances.sort_by_key(|(damage_type, _)| damage_type.to_string());
+			let resistances = resistances.iter().map(|(damage_type, resistance)| format!("{} {:+}%", damage_type.icon(), -resistance)).collect::<Vec<_>>().join("  ");
+			stats = format!("{stats}\n{resistances}");
+		}
 
 		BattlerDisplay(
 			self.name.clone(),
@@ -83,6 +138,26 @@ pub struct BattlerDisplay(
 	pub String,
 );
 
+const TEAM_ICONS: [char; 6] = ['🔴', '🔵', '🟢', '🟡', '🟣', '🟠'];
+
+impl BattlerDisplay {
+	pub fn with_team(self, team: usize) -> Self {
+		Self(format!("{} {}", TEAM_ICONS[team % TEAM_ICONS.len()], self.0), self.1, self.2)
+	}
+}
+
+/// The most characters Discord allows in a select menu option's description.
+const MAX_OPTION_DESCRIPTION: usize = 100;
+
+/// Cuts a select menu option's description short if it's longer than Discord allows.
+pub fn option_description(description: String) -> String {
+	if description.chars().count() > MAX_OPTION_DESCRIPTION {
+		description.chars().take(MAX_OPTION_DESCRIPTION - 1).chain(['…']).collect()
+	} else {
+		description
+	}
+}
+
 pub fn create_invite_action_row(c: &mut CreateComponents, disabled: bool) -> &mut CreateComponents {
 	c.create_action_row(|r|
 		r.create_button(|b|
@@ -99,4 +174,126 @@ pub fn create_invite_action_row(c: &mut CreateComponents, disabled: bool) -> &mu
 				.disabled(disabled)
 		)
 	)
+}
+
+/// Offers a rematch with new loadouts, or with the same ones if they are known.
+pub fn create_rematch_action_row(c: &mut CreateComponents, same_loadouts: bool) -> &mut CreateComponents {
+	c.create_action_row(|r|
+		r.create_button(|b|
+			b.custom_id("rematch")
+				.emoji('🔁')
+				.label("Rematch")
+				.style(ButtonStyle::Primary)
+		).create_button(|b|
+			b.custom_id("rematch_same")
+				.emoji('🎒')
+				.label("Rematch (same loadouts)")
+				.style(ButtonStyle::Secondary)
+				.disabled(!same_loadouts)
+		)
+	)
+}
+
+/// Shows the battlers and the latest log entries, with a countdown to `deadline` if given as a Unix timestamp.
+pub fn create_battle_embed<'a>(e: &'a mut CreateEmbed, battlers: &[BattlerDisplay], current: usize, log: &Log, deadline: Option<u64>) -> &'a mut CreateEmbed {
+	let current_player = &battlers[current];
+
+	let log = log.get_last_entries(3).map_or_else(|| "---".to_string(), |log| log.iter().fold(String::new(), |acc, entry| format!("{}\n{}", acc, entry)));
+
+	let e = base_embed(e)
+		.title(format!("{}'s turn", &current_player.0))
+		.fields(battlers.iter().map(|battler| (&battler.0, &battler.2, true)))
+		.field("Log", log, false);
+
+	let e = if let Some(deadline) = deadline {
+		e.description(format!("⏳ Turn ends <t:{deadline}:R>"))
+	} else {
+		e
+	};
+
+	if let Some(url) = &current_player.1 {
+		e.thumbnail(url)
+	} else {
+		e
+	}
+}
+
+pub fn create_battle_components(c: &mut CreateComponents, disabled: bool, disable_items: bool, disable_skills: bool, disable_switch: bool) -> &mut CreateComponents {
+	c.create_action_row(|r|
+		r.create_button(|b|
+			b.custom_id("attack")
+				.emoji('⚔')
+				.label("Attack")
+				.style(ButtonStyle::Primary)
+				.disabled(disabled)
+		).create_button(|b|
+			b.custom_id("switch")
+				.emoji('🔄')
+				.label("Switch")
+				.style(ButtonStyle::Primary)
+				.disabled(disabled || disable_switch)
+		).create_button( |b|
+			b.custom_id("item")
+				.emoji('🎒')
+				.label("Items...")
+				.style(ButtonStyle::Primary)
+				.disabled(disabled || disable_items)
+		).create_button(|b|
+			b.custom_id("skill")
+				.emoji('✨')
+				.label("Skills...")
+				.style(ButtonStyle::Primary)
+				.disabled(disabled || disable_skills)
+		).create_button(|b|
+			b.custom_id("defend")
+				.emoji('🛡')
+				.label("Defend")
+				.style(ButtonStyle::Primary)
+				.disabled(disabled)
+		)
+	).create_action_row(|r|
+		r.create_button(|b|
+			b.custom_id("surrender")
+				.emoji('🏳')
+				.label("Surrender")
+				.style(ButtonStyle::Danger)
+				.disabled(disabled)
+		)
+	)
+}
+
+pub fn create_replay_embed<'a>(e: &'a mut CreateEmbed, frame: &Frame, index: usize, frames: usize, seed: u64) -> &'a mut CreateEmbed {
+	let lines = if frame.lines.is_empty() {
+		"---".to_string()
+	} else {
+		frame.lines.join("\n")
+	};
+
+	base_embed(e)
+		.title(format!("📼 {}", frame.title))
+		.fields(frame.battlers.iter().map(|battler| (
+			&battler.name,
+			format!("❤ {}/{}\n🛡 {}", battler.health, battler.max_health, battler.armor),
+			true,
+		)))
+		.field("Events", lines, false)
+		.footer(|f| f.text(format!("Step {}/{} • Seed: {}", index + 1, frames, seed)))
+}
+
+pub fn create_replay_components(c: &mut CreateComponents, index: usize, frames: usize) -> &mut CreateComponents {
+	c.create_action_row(|r|
+		r.create_button(|b|
+			b.custom_id("previous")
+				.emoji('◀')
+				.label("Previous")
+				.style(ButtonStyle::Secondary)
+				.disabled(index == 0)
+		).create_button(|b|
+			b.custom_id("next")
+				.emoji('▶')
+				.label("Next")
+				.style(ButtonStyle::Secondary)
+				.disabled(index + 1 >= frames)
+		)
+	)
 }
\ No newline at end of file
@@ -0,0 +1,214 @@
+use crate::{
+	prelude::*,
+	battle::{
+		Battler,
+		damage::DamageType,
+		rarity::Rarity,
+		log::{Entry, Log},
+		replay::Event,
+		script::Script,
+		status::StatusKind,
+	},
+	model::QueryWeapon,
+};
+
+use std::{
+	collections::HashMap,
+	ops::{Bound, RangeInclusive},
+};
+use poise::serenity_prelude::ReactionType;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Weapon {
+	pub name: String,
+	pub icon: ReactionType,
+	pub damage_range: RangeInclusive<usize>,
+	pub crit_ratio: f64,
+	pub crit_multiplier: f64,
+	/// Whether critical hits go straight through armor.
+	#[serde(default)]
+	pub crit_ignores_armor: bool,
+	pub pierce: usize,
+	/// Added to the wielder's speed, so it may be negative.
+	pub speed: isize,
+	/// How many more attacks the weapon can make before it breaks, if it can break at all.
+	#[serde(default)]
+	pub durability: Option<usize>,
+	#[serde(default)]
+	pub max_durability: Option<usize>,
+	#[serde(default)]
+	pub damage_type: DamageType,
+	/// Added to the wielder's lifesteal, in percent.
+	#[serde(default)]
+	pub lifesteal: usize,
+	/// Added to the wielder's thorns, in percent.
+	#[serde(default)]
+	pub thorns: usize,
+	/// The chance of hitting an opponent that doesn't evade at all, between 0 and 1.
+	#[serde(default = "default_accuracy")]
+	pub accuracy: f64,
+	/// How likely the weapon is to be drawn for a loadout.
+	#[serde(default)]
+	pub rarity: Rarity,
+	/// Runs on every hit, after which its `on_attack`, `on_crit` and `on_kill` functions are called with the damage dealt.
+	#[serde(default)]
+	pub lua: Option<String>,
+	/// What the script has remembered so far this battle.
+	#[serde(default)]
+	pub memory: HashMap<String, f64>,
+}
+
+impl Weapon {
+	pub fn attack(&mut self, user: &mut dyn Battler, opponent: &mut dyn Battler, log: &mut Log, rng: &mut impl Rng) -> Result<(), Error> {
+		if let Some(durability) = &mut self.durability {
+			*durability = durability.saturating_sub(1);
+		}
+
+		// Focus is used up by the attack, whether or not it was needed.
+		let focused = user.remove_status(StatusKind::Focus).is_some();
+		if !focused {
+			let chance = self.accuracy * (1.0 - opponent.evasion_chance() as f64 / 100.0);
+			let hit = rng.gen_bool(chance.clamp(0.0, 1.0));
+			log.record(Event::Roll {
+				source: format!("{} hit", self.name),
+				result: hit.to_string(),
+			});
+
+			if !hit {
+				log.add(Entry::Miss(self.icon.clone(), user.name().clone(), opponent.name().clone()));
+				return Ok(());
+			}
+		}
+
+		let mut damage = rng.gen_range(self.damage_range.clone());
+		let critical = rng.gen_bool(self.crit_ratio);
+		log.record(Event::Roll {
+			source: format!("{} damage", self.name),
+			result: damage.to_string(),
+		});
+		log.record(Event::Roll {
+			source: format!("{} critical", self.name),
+			result: critical.to_string(),
+		});
+
+		if critical {
+			// Float to integer casts saturate, so huge multipliers can't overflow.
+			damage = (damage as f64 * self.crit_multiplier).round() as usize;
+		}
+
+		if let Some(weakness) = user.status(StatusKind::Weakness) {
+			let reduction = weakness.potency.saturating_mul(weakness.stacks).min(100);
+			damage = damage.saturating_sub(damage.saturating_mul(reduction) / 100);
+		}
+
+		let pierce = if critical && self.crit_ignores_armor { usize::MAX } else { self.pierce };
+		let bypassed = opponent.armor().min(pierce);
+		let damage_dealt = user.strike(opponent, damage, pierce, self.damage_type, log);
+
+		if critical {
+			log.add(Entry::Critical(self.icon.clone(), user.name().clone(), opponent.name().clone(), damage_dealt, bypassed));
+		} else {
+			log.add(Entry::Attack(self.icon.clone(), user.name().clone(), opponent.name().clone(), damage_dealt));
+		}
+
+		if let Some(lua) = &self.lua {
+			let mut script = Script::new(&self.name, &self.icon, lua).hook("on_attack", damage_dealt);
+			if critical {
+				script = script.hook("on_crit", damage_dealt);
+			}
+			if opponent.health() == 0 {
+				script = script.hook("on_kill", damage_dealt);
+			}
+			script.memory(&mut self.memory).run(user, vec![opponent], log, rng)?;
+		}
+
+		Ok(())
+	}
+
+	pub fn is_broken(&self) -> bool {
+		self.durability == Some(0)
+	}
+}
+
+fn default_accuracy() -> f64 {
+	1.0
+}
+
+/// Bare fists, which are what battlers fall back on when their weapon breaks.
+impl Default for Weapon {
+	fn default() -> Self {
+		Self {
+			name: "Fists".into(),
+			icon: '👊'.into(),
+			damage_range: 10..=20,
+			crit_ratio: 2.0 / 100.0,
+			crit_multiplier: 2.0,
+			crit_ignores_armor: false,
+			pierce: 0,
+			speed: 0,
+			durability: None,
+			max_durability: None,
+			damage_type: DamageType::Physical,
+			lifesteal: 0,
+			thorns: 0,
+			accuracy: default_accuracy(),
+			rarity: Rarity::Common,
+			lua: None,
+			memory: HashMap::new(),
+		}
+	}
+}
+
+impl TryFrom<QueryWeapon> for Weapon {
+	type Error = Error;
+
+	fn try_from(weapon: QueryWeapon) -> Result<Self, Self::Error> {
+		let damage_range = if let Some(damage_range) = weapon.damage_range {
+			match damage_range {
+				(Bound::Included(lower), Bound::Included(higher)) => (lower.max(0).try_into()?)..=(higher.max(0).try_into()?),
+				(Bound::Included(lower), Bound::Excluded(higher)) => (lower.max(0).try_into()?)..=(higher.saturating_sub(1).max(0).try_into()?),
+				(Bound::Excluded(lower), Bound::Included(higher)) => (lower.saturating_add(1).max(0).try_into()?)..=(higher.max(0).try_into()?),
+				(Bound::Excluded(lower), Bound::Excluded(higher)) => (lower.saturating_add(1).max(0).try_into()?)..=(higher.saturating_sub(1).max(0).try_into()?),
+				_ => return Err("Range cannot be open.".into()),
+			}
+		} else {
+			10..=20
+		};
+
+		let durability = weapon.durability.map(|durability| durability.max(1).try_into()).transpose()?;
+		let accuracy = weapon.accuracy.unwrap_or_else(default_accuracy);
+		if !(0.0..=1.0).contains(&accuracy) {
+			return Err(format!("The accuracy of {} must be between 0 and 1.", weapon.name).into());
+		}
+		let crit_ratio = weapon.crit_ratio.unwrap_or(2.0 / 100.0);
+		if !(0.0..=1.0).contains(&crit_ratio) {
+			return Err(format!("The crit ratio of {} must be between 0 and 1.", weapon.name).into());
+		}
+		let crit_multiplier = weapon.crit_multiplier.unwrap_or(2.0);
+		if !crit_multiplier.is_finite() || crit_multiplier < 0.0 {
+			return Err(format!("The crit multiplier of {} cannot be negative.", weapon.name).into());
+		}
+
+		Ok(Self {
+			name: weapon.name,
+			icon: weapon.icon.try_into()?,
+			damage_range,
+			crit_ratio,
+			crit_multiplier,
+			crit_ignores_armor: weapon.crit_ignores_armor,
+			pierce: weapon.pierce.unwrap_or(0).max(0).try_into()?,
+			speed: weapon.speed.unwrap_or(0).try_into()?,
+			durability,
+			max_durability: durability,
+			damage_type: weapon.damage_type.as_deref().map_or(Ok(DamageType::Physical), str::parse)?,
+			lifesteal: weapon.lifesteal.unwrap_or(0).max(0).try_into()?,
+			thorns: weapon.thorns.unwrap_or(0).max(0).try_into()?,
+			accuracy,
+			rarity: weapon.rarity.parse()?,
+			lua: weapon.lua,
+			memory: HashMap::new(),
+		})
+	}
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use rpgbot::{
+	battle::{Action, Armory, Balanced, BattleEngine, BattleRng, Battler, NpcBattler, TurnOrder},
+	types::Config,
+};
+use std::{collections::BTreeMap, env, error::Error};
+use dotenv::dotenv;
+use uuid::Uuid;
+
+const DEFAULT_BATTLES: usize = 1000;
+const MAX_TURNS: usize = 1000;
+
+#[derive(Default)]
+struct Stats {
+	appearances: usize,
+	wins: usize,
+	uses: usize,
+	damage: usize,
+}
+
+impl Stats {
+	fn win_rate(&self) -> f64 {
+		self.wins as f64 / self.appearances.max(1) as f64 * 100.0
+	}
+
+	fn average_damage(&self) -> f64 {
+		self.damage as f64 / self.uses.max(1) as f64
+	}
+}
+
+fn print_stats(title: &str, stats: &BTreeMap<String, Stats>) {
+	println!("\n{title}");
+	println!("{:<24} {:>8} {:>8} {:>8} {:>12}", "Name", "Seen", "Win %", "Uses", "Avg damage");
+	for (name, stats) in stats {
+		println!("{:<24} {:>8} {:>8.1} {:>8} {:>12.1}", name, stats.appearances, stats.win_rate(), stats.uses, stats.average_damage());
+	}
+}
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+	dotenv().ok();
+	let battles = match env::args().nth(1) {
+		Some(battles) => battles.parse()?,
+		None => DEFAULT_BATTLES,
+	};
+
+	let armory = Armory::load()?.with_weights(Config::from_env()?.rarity_weights);
+
+	let mut weapons: BTreeMap<String, Stats> = BTreeMap::new();
+	let mut items: BTreeMap<String, Stats> = BTreeMap::new();
+	let mut skills: BTreeMap<String, Stats> = BTreeMap::new();
+	let mut total_turns = 0;
+	let mut ties = 0;
+
+	for _ in 0..battles {
+		let mut rng = BattleRng::new();
+		let battlers: Vec<Box<dyn Battler>> = vec![
+			Box::new(NpcBattler::new("Red", None, Box::new(Balanced::default()), &armory, &mut rng)?),
+			Box::new(NpcBattler::new("Blue", None, Box::new(Balanced::default()), &armory, &mut rng)?),
+		];
+
+		let loadouts: Vec<(Uuid, String, Vec<String>, Vec<String>)> = battlers
+			.iter()
+			.map(|battler| (
+				*battler.id(),
+				battler.weapon().name.clone(),
+				battler.items().values().map(|item| item.name.clone()).collect(),
+				battler.skills().iter().map(|skill| skill.name.clone()).collect(),
+			))
+			.collect();
+
+		for (_, weapon, battler_items, battler_skills) in &loadouts {
+			weapons.entry(weapon.clone()).or_default().appearances += 1;
+			for item in battler_items {
+				items.entry(item.clone()).or_default().appearances += 1;
+			}
+			for skill in battler_skills {
+				skills.entry(skill.clone()).or_default().appearances += 1;
+			}
+		}
+
+		let teams = battlers.into_iter().map(|battler| vec![battler]).collect();
+		let mut engine = BattleEngine::new(teams, TurnOrder::Alternating, rng);
+		let mut turns = 0;
+
+		while !engine.is_over() && turns < MAX_TURNS {
+			if !engine.begin_turn() {
+				turns += 1;
+				continue;
+			}
+
+			let action = engine.decide().ok_or("Only NPCs can battle in the simulator.")?;
+			let stats = match &action {
+				Action::Attack { .. } => Some(weapons.entry(engine.current().weapon().name.clone()).or_default()),
+				Action::Item { item, .. } => engine.current().items().get(item).map(|item| items.entry(item.name.clone()).or_default()),
+				Action::Skill { skill, .. } => engine.current().skills().iter().find(|s| s.id == *skill).map(|skill| skills.entry(skill.name.clone()).or_default()),
+				_ => None,
+			};
+
+			let turn = engine.turn();
+			let health_before: Vec<usize> = engine.battlers().iter().map(|battler| battler.health()).collect();
+
+			engine.act(action)?;
+
+			let damage: usize = engine.battlers()
+				.iter()
+				.zip(health_before)
+				.enumerate()
+				.filter(|(i, _)| engine.team(*i) != engine.team(turn))
+				.map(|(_, (battler, health))| health.saturating_sub(battler.health()))
+				.sum();
+
+			if let Some(stats) = stats {
+				stats.uses += 1;
+				stats.damage += damage;
+			}
+
+			turns += 1;
+		}
+
+		total_turns += turns;
+
+		let winners = engine.winners();
+		if winners.is_empty() {
+			ties += 1;
+		}
+
+		for winner in winners {
+			let (_, weapon, winner_items, winner_skills) = loadouts
+				.iter()
+				.find(|(id, _, _, _)| id == winner.id())
+				.ok_or("Winner not found.")?;
+
+			weapons.entry(weapon.clone()).or_default().wins += 1;
+			for item in winner_items {
+				items.entry(item.clone()).or_default().wins += 1;
+			}
+			for skill in winner_skills {
+				skills.entry(skill.clone()).or_default().wins += 1;
+			}
+		}
+	}
+
+	println!("Simulated {battles} battles ({ties} ties), averaging {:.1} turns per battle.", total_turns as f64 / battles.max(1) as f64);
+	print_stats("Weapons", &weapons);
+	print_stats("Items", &items);
+	print_stats("Skills", &skills);
+
+	Ok(())
+}
@@ -1,8 +1,36 @@
 use crate::{
-	battle::Battle,
+	battle::{Battle, Replay, TurnOrder},
 	prelude::*,
 };
 use poise::serenity_prelude::User;
+use uuid::Uuid;
+
+/// Checks that the author can battle the given users, telling them why not otherwise.
+async fn check_participants(ctx: Context<'_>, kind: &str, users: &[&User]) -> Result<bool, Error> {
+	let author = ctx.author();
+
+	let reason = if users.iter().any(|user| user.bot) {
+		Some(format!("You cannot challenge bots to a {kind}."))
+	} else if users.contains(&author) {
+		Some(format!("You cannot challenge yourself to a {kind}."))
+	} else if users.iter().enumerate().any(|(i, user)| users[..i].contains(user)) {
+		Some("You cannot challenge the same user twice.".to_string())
+	} else if ctx.data().battles.is_in_battle(&author.id) {
+		Some("You cannot be in two battles at once.".to_string())
+	} else {
+		users
+			.iter()
+			.find(|user| ctx.data().battles.is_in_battle(&user.id))
+			.map(|user| format!("{} is currently in a battle. Try again later.", user.name))
+	};
+
+	if let Some(reason) = reason {
+		ctx.send(|m| m.content(&reason).ephemeral(true)).await?;
+		Ok(false)
+	} else {
+		Ok(true)
+	}
+}
 
 /// Duel a user.
 #[poise::command(
@@ -14,35 +42,138 @@ pub async fn duel(
 	ctx: Context<'_>,
 	#[description = "User to duel."] opponent: User,
 ) -> Result<(), Error> {
-	let p1 = ctx.author().clone();
+	if !check_participants(ctx, "duel", &[&opponent]).await? {
+		return Ok(());
+	}
 
-	if opponent.bot {
-		ctx.send(|m| m.content("You cannot challenge bots to a duel.").ephemeral(true)).await?;
+	let teams = vec![vec![ctx.author().clone()], vec![opponent]];
+	if let Err(e) = Battle::send_invite(ctx, teams, TurnOrder::Alternating).await {
+		eprintln!("{:?}", e);
+		return Err("There was an error during the battle.".into());
+	};
+	Ok(())
+}
+
+/// Start a 2v2 or 3v3 team battle.
+#[poise::command(
+	slash_command,
+	guild_only,
+	user_cooldown = 10,
+)]
+pub async fn teams(
+	ctx: Context<'_>,
+	#[description = "Your teammate."] ally: User,
+	#[description = "First opponent."] opponent1: User,
+	#[description = "Second opponent."] opponent2: User,
+	#[description = "Your second teammate, for a 3v3."] ally2: Option<User>,
+	#[description = "Third opponent, for a 3v3."] opponent3: Option<User>,
+) -> Result<(), Error> {
+	if ally2.is_some() != opponent3.is_some() {
+		ctx.send(|m| m.content("Both teams must be the same size.").ephemeral(true)).await?;
 		return Ok(());
 	}
 
-	if p1 == opponent {
-		ctx.send(|m| m.content("You cannot challenge yourself to a duel.").ephemeral(true)).await?;
+	let allies: Vec<User> = [Some(ctx.author().clone()), Some(ally), ally2].into_iter().flatten().collect();
+	let opponents: Vec<User> = [Some(opponent1), Some(opponent2), opponent3].into_iter().flatten().collect();
+	let users: Vec<&User> = allies[1..].iter().chain(&opponents).collect();
+	if !check_participants(ctx, "team battle", &users).await? {
 		return Ok(());
 	}
 
-	let data = ctx.data();
-	if data.check_for_user_in_battle(&p1) {
-		ctx.send(|m| m.content("You cannot be in two battles at once.").ephemeral(true)).await?;
+	if let Err(e) = Battle::send_invite(ctx, vec![allies, opponents], TurnOrder::Alternating).await {
+		eprintln!("{:?}", e);
+		return Err("There was an error during the battle.".into());
+	};
+	Ok(())
+}
+
+/// Start a free-for-all.
+#[poise::command(
+	slash_command,
+	guild_only,
+	user_cooldown = 10,
+)]
+pub async fn brawl(
+	ctx: Context<'_>,
+	#[description = "First opponent."] opponent1: User,
+	#[description = "Second opponent."] opponent2: User,
+	#[description = "Third opponent."] opponent3: Option<User>,
+	#[description = "Fourth opponent."] opponent4: Option<User>,
+	#[description = "Fifth opponent."] opponent5: Option<User>,
+) -> Result<(), Error> {
+	let opponents: Vec<User> = [Some(opponent1), Some(opponent2), opponent3, opponent4, opponent5].into_iter().flatten().collect();
+	let users: Vec<&User> = opponents.iter().collect();
+	if !check_participants(ctx, "free-for-all", &users).await? {
 		return Ok(());
 	}
-	if data.check_for_user_in_battle(&opponent) {
-		ctx.send(|m| m.content("That user is currently in a battle. Try again later.").ephemeral(true)).await?;
+
+	let teams = std::iter::once(ctx.author().clone())
+		.chain(opponents)
+		.map(|user| vec![user])
+		.collect();
+	if let Err(e) = Battle::send_invite(ctx, teams, TurnOrder::Random).await {
+		eprintln!("{:?}", e);
+		return Err("There was an error during the battle.".into());
+	};
+	Ok(())
+}
+
+/// Fight the bot.
+#[poise::command(
+	slash_command,
+	guild_only,
+	user_cooldown = 10,
+)]
+pub async fn fight(ctx: Context<'_>) -> Result<(), Error> {
+	let user = ctx.author().clone();
+
+	if ctx.data().battles.is_in_battle(&user.id) {
+		ctx.send(|m| m.content("You cannot be in two battles at once.").ephemeral(true)).await?;
 		return Ok(());
 	}
 
-	if let Err(e) = Battle::send_invite(ctx, p1, opponent).await {
+	if let Err(e) = Battle::send_fight(ctx, user).await {
 		eprintln!("{:?}", e);
 		return Err("There was an error during the battle.".into());
 	};
 	Ok(())
 }
 
+/// Replay a finished battle.
+#[poise::command(
+	slash_command,
+	guild_only,
+)]
+pub async fn replay(
+	ctx: Context<'_>,
+	#[description = "ID of the battle to replay."] id: String,
+) -> Result<(), Error> {
+	let id = if let Ok(id) = Uuid::parse_str(id.trim()) {
+		id
+	} else {
+		ctx.send(|m| m.content("That is not a valid battle ID.").ephemeral(true)).await?;
+		return Ok(());
+	};
+
+	let replay = match Replay::load(&id) {
+		Ok(Some(replay)) => replay,
+		Ok(None) => {
+			ctx.send(|m| m.content("No replay was found for that battle.").ephemeral(true)).await?;
+			return Ok(());
+		},
+		Err(e) => {
+			eprintln!("{:?}", e);
+			return Err("There was an error while loading the replay.".into());
+		},
+	};
+
+	if let Err(e) = replay.show(ctx).await {
+		eprintln!("{:?}", e);
+		return Err("There was an error during the replay.".into());
+	};
+	Ok(())
+}
+
 /// Displays a menu for registering slash commands.
 #[poise::command(
 	prefix_command,
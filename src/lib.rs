@@ -1,25 +1,41 @@
 #[macro_use]
 extern crate diesel;
 
-mod battle;
 mod commands;
 
+pub mod battle;
 pub mod model;
 pub mod prelude;
 pub mod schema;
 pub mod types;
 pub mod util;
 
-use commands::{duel, register};
+use battle::Battle;
+use commands::{brawl, duel, fight, register, replay, teams};
 use prelude::*;
 
 use std::collections::HashSet;
 use poise::{
 	BoxFuture,
+	Event,
 	Framework,
+	FrameworkContext,
 	serenity_prelude as serenity,
 };
 
+/// Picks saved battles back up once the bot is ready.
+///
+/// Resumed battles run inside this listener until they finish.
+fn listener<'a>(ctx: &'a serenity::Context, event: &'a Event<'a>, _framework: FrameworkContext<'a, Data, Error>, data: &'a Data) -> BoxFuture<'a, Result<(), Error>> {
+	Box::pin(async move {
+		if let Event::Ready { .. } = event {
+			Battle::resume_all(ctx, data).await?;
+		}
+
+		Ok(())
+	})
+}
+
 pub async fn start<T, U>(token: T, owner_ids: Vec<U>) -> Result<(), Error>
 	where
 		T: Into<String> + Send + Sync,
@@ -32,13 +48,14 @@ pub async fn start<T, U>(token: T, owner_ids: Vec<U>) -> Result<(), Error>
 
 	let framework = Framework::builder()
 		.options(poise::FrameworkOptions{
-			commands: vec![duel(), register()],
+			commands: vec![brawl(), duel(), fight(), register(), replay(), teams()],
+			listener,
 			owners,
 			..Default::default()
 		})
 		.token(token)
 		.intents(serenity::GatewayIntents::non_privileged())
-		.user_data_setup(move |_ctx, _ready, _framework| -> BoxFuture<'_, Result<Data, Error>> { Box::pin(async move { Ok(Data {..Default::default()}) }) });
+		.user_data_setup(move |_ctx, _ready, _framework| -> BoxFuture<'_, Result<Data, Error>> { Box::pin(async move { Ok(Data { config: Config::from_env()?, ..Default::default() }) }) });
 
 	framework.run().await?;
 
@@ -1,3 +1,5 @@
+use crate::schema::{battles, replays};
+
 use std::ops::Bound;
 
 #[derive(Queryable)]
@@ -7,6 +9,20 @@ pub struct QueryItem {
 	pub description: String,
 	pub icon: String,
 	pub lua: String,
+	pub area: bool,
+	pub rarity: String,
+}
+
+#[derive(Queryable)]
+pub struct QuerySkill {
+	pub id: i32,
+	pub name: String,
+	pub description: String,
+	pub icon: String,
+	pub lua: String,
+	pub cost: i32,
+	pub cooldown: i32,
+	pub area: bool,
 }
 
 #[derive(Queryable)]
@@ -16,6 +32,49 @@ pub struct QueryWeapon {
 	pub icon: String,
 	pub damage_range: Option<(Bound<i32>, Bound<i32>)>,
 	pub crit_ratio: Option<f64>,
-	pub crit_multiplier: Option<i32>,
+	pub crit_multiplier: Option<f64>,
 	pub pierce: Option<i32>,
+	pub speed: Option<i32>,
+	pub damage_type: Option<String>,
+	pub lua: Option<String>,
+	pub durability: Option<i32>,
+	pub accuracy: Option<f64>,
+	pub crit_ignores_armor: bool,
+	pub lifesteal: Option<i32>,
+	pub thorns: Option<i32>,
+	pub rarity: String,
+}
+
+#[derive(Queryable)]
+pub struct QueryReplay {
+	pub id: String,
+	pub seed: i64,
+	pub events: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "replays"]
+pub struct NewReplay<'a> {
+	pub id: &'a str,
+	pub seed: i64,
+	pub events: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct QueryBattle {
+	pub id: String,
+	pub guild_id: i64,
+	pub channel_id: i64,
+	pub message_id: i64,
+	pub state: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "battles"]
+pub struct NewBattle<'a> {
+	pub id: &'a str,
+	pub guild_id: i64,
+	pub channel_id: i64,
+	pub message_id: i64,
+	pub state: &'a str,
 }
\ No newline at end of file
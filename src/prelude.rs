@@ -1 +1 @@
-pub use crate::types::{Context, Data, Error};
\ No newline at end of file
+pub use crate::types::{BattleRegistry, Config, Context, Data, Error, Reservation};
\ No newline at end of file
@@ -1,3 +1,13 @@
+table! {
+    battles (id) {
+        id -> Varchar,
+        guild_id -> Int8,
+        channel_id -> Int8,
+        message_id -> Int8,
+        state -> Text,
+    }
+}
+
 table! {
     items (id) {
         id -> Int4,
@@ -5,6 +15,29 @@ table! {
         description -> Text,
         icon -> Bpchar,
         lua -> Text,
+        area -> Bool,
+        rarity -> Varchar,
+    }
+}
+
+table! {
+    replays (id) {
+        id -> Varchar,
+        seed -> Int8,
+        events -> Text,
+    }
+}
+
+table! {
+    skills (id) {
+        id -> Int4,
+        name -> Varchar,
+        description -> Text,
+        icon -> Bpchar,
+        lua -> Text,
+        cost -> Int4,
+        cooldown -> Int4,
+        area -> Bool,
     }
 }
 
@@ -15,12 +48,24 @@ table! {
         icon -> Bpchar,
         damage_range -> Nullable<Int4range>,
         crit_ratio -> Nullable<Float8>,
-        crit_multiplier -> Nullable<Int4>,
+        crit_multiplier -> Nullable<Float8>,
         pierce -> Nullable<Int4>,
+        speed -> Nullable<Int4>,
+        damage_type -> Nullable<Varchar>,
+        lua -> Nullable<Text>,
+        durability -> Nullable<Int4>,
+        accuracy -> Nullable<Float8>,
+        crit_ignores_armor -> Bool,
+        lifesteal -> Nullable<Int4>,
+        thorns -> Nullable<Int4>,
+        rarity -> Varchar,
     }
 }
 
 allow_tables_to_appear_in_same_query!(
+    battles,
     items,
+    replays,
+    skills,
     weapons,
 );
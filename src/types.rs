@@ -1,17 +1,189 @@
-use std::{collections::HashMap, error, sync::RwLock};
-use poise::serenity_prelude::{User, UserId};
+use std::{
+	collections::HashMap,
+	env,
+	error,
+	sync::RwLock,
+	time::{Duration, SystemTime},
+};
+use crate::battle::{DEFAULT_MAX_TIMEOUTS, RarityWeights};
+use poise::serenity_prelude::{ChannelId, GuildId, MessageId, UserId};
 use uuid::Uuid;
 
 pub type Error = Box<dyn error::Error + Send + Sync>;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 
+/// Settings read from the environment, falling back to the defaults for anything unset.
+#[derive(Debug)]
+pub struct Config {
+	/// How long a user has to take their turn, read from `TURN_TIMEOUT` in seconds.
+	pub turn_timeout: Duration,
+	/// How many turns in a row a user can time out before forfeiting, read from `MAX_TIMEOUTS`.
+	pub max_timeouts: usize,
+	/// Whether switching weapons leaves the turn to do something else, read from `FREE_SWITCH`.
+	pub free_switch: bool,
+	/// How likely each rarity is to be drawn for loadouts, read from `RARITY_WEIGHTS` as four comma-separated numbers.
+	pub rarity_weights: RarityWeights,
+}
+
+impl Config {
+	pub fn from_env() -> Result<Self, Error> {
+		let mut config = Self::default();
+		if let Ok(turn_timeout) = env::var("TURN_TIMEOUT") {
+			config.turn_timeout = Duration::from_secs(turn_timeout.parse()?);
+		}
+		if let Ok(max_timeouts) = env::var("MAX_TIMEOUTS") {
+			config.max_timeouts = max_timeouts.parse()?;
+		}
+		if let Ok(free_switch) = env::var("FREE_SWITCH") {
+			config.free_switch = free_switch.parse()?;
+		}
+		if let Ok(rarity_weights) = env::var("RARITY_WEIGHTS") {
+			config.rarity_weights = rarity_weights.parse()?;
+		}
+		Ok(config)
+	}
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			turn_timeout: Duration::from_secs(120),
+			max_timeouts: DEFAULT_MAX_TIMEOUTS,
+			free_switch: false,
+			rarity_weights: RarityWeights::default(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleState {
+	/// Waiting for everyone to accept the invitation.
+	Pending,
+	Running,
+}
+
+/// What the registry knows about a battle.
+#[derive(Debug, Clone)]
+pub struct BattleEntry {
+	pub guild_id: GuildId,
+	pub channel_id: ChannelId,
+	/// The message the battle is shown in, once it has been sent.
+	pub message_id: Option<MessageId>,
+	pub started: SystemTime,
+	pub state: BattleState,
+	pub users: Vec<UserId>,
+}
+
 #[derive(Debug, Default)]
-pub struct Data {
-	pub battles: RwLock<HashMap<Uuid, Vec<Option<UserId>>>>,
+struct Registry {
+	battles: HashMap<Uuid, BattleEntry>,
+	users: HashMap<UserId, Uuid>,
 }
 
-impl Data {
-	pub fn check_for_user_in_battle(&self, user: &User) -> bool {
-		self.battles.read().unwrap().values().flatten().any(|id| id.as_ref() == Some(&user.id))
+/// Every pending and running battle, indexed by the users in them.
+///
+/// Users are reserved as soon as they are invited, so nobody can be pulled into two battles at once.
+#[derive(Debug, Default)]
+pub struct BattleRegistry {
+	inner: RwLock<Registry>,
+}
+
+impl BattleRegistry {
+	/// Registers a pending battle and reserves its users, unless one of them is already in a battle.
+	///
+	/// Returns the first user that is already in a battle on failure.
+	/// The battle stays registered until the returned reservation is dropped.
+	pub fn reserve(&self, id: Uuid, guild_id: GuildId, channel_id: ChannelId, users: Vec<UserId>) -> Result<Reservation<'_>, UserId> {
+		let mut inner = self.inner.write().unwrap();
+		if let Some(user) = users.iter().find(|user| inner.users.contains_key(user)) {
+			return Err(*user);
+		}
+
+		for user in &users {
+			inner.users.insert(*user, id);
+		}
+		inner.battles.insert(id, BattleEntry {
+			guild_id,
+			channel_id,
+			message_id: None,
+			started: SystemTime::now(),
+			state: BattleState::Pending,
+			users,
+		});
+
+		Ok(Reservation { registry: self, id })
+	}
+
+	/// The battle a user is in, if any.
+	pub fn battle_of(&self, user: &UserId) -> Option<Uuid> {
+		self.inner.read().unwrap().users.get(user).copied()
+	}
+
+	pub fn is_in_battle(&self, user: &UserId) -> bool {
+		self.inner.read().unwrap().users.contains_key(user)
 	}
-}
\ No newline at end of file
+
+	pub fn contains(&self, id: &Uuid) -> bool {
+		self.inner.read().unwrap().battles.contains_key(id)
+	}
+
+	pub fn get(&self, id: &Uuid) -> Option<BattleEntry> {
+		self.inner.read().unwrap().battles.get(id).cloned()
+	}
+
+	pub fn len(&self) -> usize {
+		self.inner.read().unwrap().battles.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	fn release(&self, id: &Uuid) {
+		let mut inner = self.inner.write().unwrap();
+		if let Some(entry) = inner.battles.remove(id) {
+			for user in entry.users {
+				inner.users.remove(&user);
+			}
+		}
+	}
+}
+
+/// Keeps a battle and its users registered until it is dropped.
+#[derive(Debug)]
+pub struct Reservation<'a> {
+	registry: &'a BattleRegistry,
+	id: Uuid,
+}
+
+impl Reservation<'_> {
+	pub fn id(&self) -> &Uuid {
+		&self.id
+	}
+
+	pub fn set_message(&self, message_id: MessageId) {
+		if let Some(entry) = self.registry.inner.write().unwrap().battles.get_mut(&self.id) {
+			entry.message_id = Some(message_id);
+		}
+	}
+
+	/// Marks the battle as running, such as once everyone has accepted the invitation.
+	pub fn start(&self) {
+		if let Some(entry) = self.registry.inner.write().unwrap().battles.get_mut(&self.id) {
+			entry.state = BattleState::Running;
+			entry.started = SystemTime::now();
+		}
+	}
+}
+
+impl Drop for Reservation<'_> {
+	fn drop(&mut self) {
+		self.registry.release(&self.id);
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct Data {
+	pub battles: BattleRegistry,
+	pub config: Config,
+}
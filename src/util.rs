@@ -1,5 +1,17 @@
+use crate::prelude::*;
+
+use std::env;
+use diesel::{
+	prelude::*,
+	pg::PgConnection,
+};
 use poise::serenity_prelude::CreateEmbed;
 
 pub fn base_embed(e: &mut CreateEmbed) -> &mut CreateEmbed {
 	e.color((0x51, 0x68, 0xf2))
+}
+
+pub fn establish_connection() -> Result<PgConnection, Error> {
+	let database_url = env::var("DATABASE_URL")?;
+	Ok(PgConnection::establish(&database_url)?)
 }
\ No newline at end of file